@@ -0,0 +1,256 @@
+use duck_husky_wedding::PlayerKind;
+use errors::*;
+
+use std::vec;
+
+pub const USAGE: &'static str = "\
+usage: duck_husky_wedding [--data-dir <dir>] [<command>]
+
+commands:
+    play [--level <file>] [--character <duck|husky>] [--windowed]
+            play the campaign; the default. --level takes the file of a campaign level
+    view [<level>]
+            open a level in the editor
+    validate <level>
+            list everything wrong with a level
+    replay <file> [--headless] [--script]
+            watch a recorded run; scripts are always run headless. headless runs exit
+            with 0 when the level is finished, 1 when it is not and 3 when the run
+            cannot be made at all
+    help
+            show this message
+
+--data-dir points at the media directory, or a tar archive of it, to load from;
+DUCK_HUSKY_WEDDING_DATA does the same when the flag is not given";
+
+// flags that are followed by a value
+static VALUED: [&'static str; 3] = ["--data-dir", "--level", "--character"];
+
+pub struct Play {
+    pub level: Option<String>,
+    pub character: Option<PlayerKind>,
+    pub windowed: bool,
+}
+
+pub enum Command {
+    Play(Play),
+    View(Option<String>),
+    Validate(String),
+    Replay {
+        file: String,
+        headless: bool,
+        script: bool,
+    },
+    Help,
+}
+
+pub struct Cli {
+    pub data_dir: Option<String>,
+    pub command: Command,
+}
+
+struct Args {
+    words: vec::IntoIter<String>,
+    flags: Vec<(String, Option<String>)>,
+}
+
+impl Args {
+    fn split<I: Iterator<Item = String>>(mut args: I) -> Result<Self> {
+        let mut words = vec![];
+        let mut flags = vec![];
+        while let Some(a) = args.next() {
+            if !a.starts_with("--") {
+                words.push(a);
+            } else if VALUED.contains(&&*a) {
+                let value = args.next().ok_or_else(|| format!("{} needs a value", a))?;
+                flags.push((a, Some(value)));
+            } else {
+                flags.push((a, None));
+            }
+        }
+        Ok(Args {
+            words: words.into_iter(),
+            flags,
+        })
+    }
+
+    fn word(&mut self) -> Option<String> {
+        self.words.next()
+    }
+
+    fn flag(&mut self, name: &str) -> bool {
+        self.take(name).is_some()
+    }
+
+    fn value(&mut self, name: &str) -> Option<String> {
+        self.take(name).and_then(|v| v)
+    }
+
+    fn take(&mut self, name: &str) -> Option<Option<String>> {
+        self.flags
+            .iter()
+            .position(|&(ref f, _)| f == name)
+            .map(|i| self.flags.remove(i).1)
+    }
+
+    // anything not asked for by the command is a mistake
+    fn finish(mut self) -> Result<()> {
+        if let Some(w) = self.words.next() {
+            bail!("unexpected argument: {}", w);
+        }
+        if let Some(&(ref f, _)) = self.flags.first() {
+            bail!("unexpected flag: {}", f);
+        }
+        Ok(())
+    }
+}
+
+fn character(name: &str) -> Result<PlayerKind> {
+    match &*name.to_lowercase() {
+        "duck" => Ok(PlayerKind::Duck),
+        "husky" => Ok(PlayerKind::Husky),
+        _ => bail!("unknown character: {}; pick duck or husky", name),
+    }
+}
+
+pub fn parse<I: Iterator<Item = String>>(args: I) -> Result<Cli> {
+    let mut args = Args::split(args)?;
+    let data_dir = args.value("--data-dir");
+    let name = args.word().unwrap_or_else(|| String::from("play"));
+    let command = match &*name {
+        "play" => {
+            let character = match args.value("--character") {
+                Some(c) => Some(character(&c)?),
+                None => None,
+            };
+            Command::Play(Play {
+                level: args.value("--level"),
+                character,
+                windowed: args.flag("--windowed"),
+            })
+        }
+        "view" => Command::View(args.word()),
+        "validate" => Command::Validate(args.word().ok_or("validate needs a level")?),
+        "replay" => Command::Replay {
+            file: args.word().ok_or("replay needs a file")?,
+            headless: args.flag("--headless"),
+            script: args.flag("--script"),
+        },
+        "help" => Command::Help,
+        _ => bail!("unknown command: {}", name),
+    };
+    let help = args.flag("--help");
+    args.finish()?;
+    Ok(Cli {
+        data_dir,
+        command: if help { Command::Help } else { command },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_all(args: &[&str]) -> Result<Cli> {
+        parse(args.iter().map(|a| a.to_string()))
+    }
+
+    fn error(args: &[&str]) -> String {
+        match parse_all(args) {
+            Ok(_) => panic!("{:?} should not parse", args),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[test]
+    fn plays_by_default() {
+        let cli = parse_all(&[]).unwrap();
+        assert_eq!(cli.data_dir, None);
+        match cli.command {
+            Command::Play(p) => {
+                assert_eq!(p.level, None);
+                assert_eq!(p.character, None);
+                assert!(!p.windowed);
+            }
+            _ => panic!("expected play"),
+        }
+    }
+
+    #[test]
+    fn reads_play_flags_in_any_order() {
+        let args = [
+            "--windowed",
+            "play",
+            "--character",
+            "Husky",
+            "--data-dir",
+            "media.tar",
+            "--level",
+            "level.yaml",
+        ];
+        let cli = parse_all(&args).unwrap();
+        assert_eq!(cli.data_dir, Some(String::from("media.tar")));
+        match cli.command {
+            Command::Play(p) => {
+                assert_eq!(p.level, Some(String::from("level.yaml")));
+                assert_eq!(p.character, Some(PlayerKind::Husky));
+                assert!(p.windowed);
+            }
+            _ => panic!("expected play"),
+        }
+    }
+
+    #[test]
+    fn reads_the_other_commands() {
+        match parse_all(&["view"]).unwrap().command {
+            Command::View(None) => {}
+            _ => panic!("expected view without a level"),
+        }
+        match parse_all(&["view", "level.yaml"]).unwrap().command {
+            Command::View(Some(l)) => assert_eq!(l, "level.yaml"),
+            _ => panic!("expected view with a level"),
+        }
+        match parse_all(&["validate", "level.yaml"]).unwrap().command {
+            Command::Validate(l) => assert_eq!(l, "level.yaml"),
+            _ => panic!("expected validate"),
+        }
+        match parse_all(&["replay", "run.yaml", "--script"]).unwrap().command {
+            Command::Replay {
+                file,
+                headless,
+                script,
+            } => {
+                assert_eq!(file, "run.yaml");
+                assert!(!headless);
+                assert!(script);
+            }
+            _ => panic!("expected replay"),
+        }
+    }
+
+    #[test]
+    fn help_wins_over_the_command() {
+        match parse_all(&["replay", "run.yaml", "--help"]).unwrap().command {
+            Command::Help => {}
+            _ => panic!("expected help"),
+        }
+        match parse_all(&["help"]).unwrap().command {
+            Command::Help => {}
+            _ => panic!("expected help"),
+        }
+    }
+
+    #[test]
+    fn rejects_mistakes() {
+        assert_eq!(error(&["fly"]), "unknown command: fly");
+        assert_eq!(error(&["play", "--level"]), "--level needs a value");
+        assert_eq!(error(&["validate"]), "validate needs a level");
+        assert_eq!(error(&["replay"]), "replay needs a file");
+        assert_eq!(error(&["view", "a.yaml", "b.yaml"]), "unexpected argument: b.yaml");
+        assert_eq!(error(&["view", "--headless"]), "unexpected flag: --headless");
+        assert_eq!(
+            error(&["--character", "cat"]),
+            "unknown character: cat; pick duck or husky"
+        );
+    }
+}
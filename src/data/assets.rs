@@ -0,0 +1,451 @@
+use errors::*;
+
+use sdl2::filesystem;
+
+use std::cell::RefCell;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read};
+use std::path::{Component, Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+// points at a data directory or archive when there is no --data-dir
+const ENV_VAR: &'static str = "DUCK_HUSKY_WEDDING_DATA";
+const DIR: &'static str = "media";
+// made with `tar -cf media.tar -C media .`
+const ARCHIVE: &'static str = "media.tar";
+const ORGANIZATION: &'static str = "nrxus";
+const APPLICATION: &'static str = "duck_husky_wedding";
+const BLOCK: usize = 512;
+
+struct Roots {
+    media: PathBuf,
+    // where the player's files go; the data directory when the platform has nowhere better
+    user: Option<PathBuf>,
+}
+
+thread_local! {
+    // only changed at start up
+    static ROOTS: RefCell<Roots> = RefCell::new(Roots {
+        media: PathBuf::from(DIR),
+        user: None,
+    });
+}
+
+// a path given by the player has to be there; the others are only looked for
+fn given(path: Option<String>, what: &str) -> Result<Option<PathBuf>> {
+    match path.map(PathBuf::from) {
+        Some(ref p) if !p.exists() => bail!("{} {:?} does not exist", what, p),
+        p => Ok(p),
+    }
+}
+
+// the data directory or archive inside `dir`, the directory winning when there are both
+fn inside(dir: &Path) -> Option<PathBuf> {
+    [dir.join(DIR), dir.join(ARCHIVE)]
+        .iter()
+        .find(|p| p.exists())
+        .cloned()
+}
+
+// in order: `flag`, `env`, next to the executable in `exe_dir` and finally `cwd`, where
+// the directory is assumed when there is nothing at all
+fn choose(
+    flag: Option<String>,
+    env: Option<String>,
+    exe_dir: Option<&Path>,
+    cwd: &Path,
+) -> Result<PathBuf> {
+    if let Some(p) = given(flag, "--data-dir")? {
+        return Ok(p);
+    }
+    if let Some(p) = given(env, ENV_VAR)? {
+        return Ok(p);
+    }
+    Ok(exe_dir
+        .and_then(inside)
+        .or_else(|| inside(cwd))
+        .unwrap_or_else(|| cwd.join(DIR)))
+}
+
+// has to be called before anything is loaded; a --data-dir in `flag` that is not there
+// is an error rather than falling back to somewhere else
+pub fn locate(flag: Option<String>) -> Result<()> {
+    let user = match filesystem::pref_path(ORGANIZATION, APPLICATION) {
+        Ok(p) => Some(PathBuf::from(p)),
+        Err(e) => {
+            println!("no user data directory; saving next to the data: {}", e);
+            None
+        }
+    };
+    let found = {
+        let exe = env::current_exe().ok();
+        let exe_dir = exe.as_ref().and_then(|e| e.parent());
+        choose(flag, env::var(ENV_VAR).ok(), exe_dir, Path::new(""))
+    }?;
+    let media = if found.is_file() {
+        let dir = user.as_ref()
+            .map_or_else(|| env::temp_dir().join(APPLICATION), |u| u.to_path_buf())
+            .join("unpacked");
+        unpacked(&found, &dir).chain_err(|| format!("cannot unpack archive: {:?}", found))?
+    } else {
+        found
+    };
+    ROOTS.with(|r| *r.borrow_mut() = Roots { media, user });
+    Ok(())
+}
+
+// `path` inside the data directory
+pub fn media(path: &str) -> String {
+    ROOTS.with(|r| r.borrow().media.join(path).to_string_lossy().into_owned())
+}
+
+// `path` inside the player's own directory
+pub fn user(path: &str) -> String {
+    ROOTS.with(|r| {
+        let roots = r.borrow();
+        let root = roots.user.as_ref().unwrap_or(&roots.media);
+        root.join(path).to_string_lossy().into_owned()
+    })
+}
+
+// falls back to the data directory, where files were saved before there was a user one
+pub fn open_user(path: &str) -> io::Result<File> {
+    File::open(user(path)).or_else(|_| File::open(media(path)))
+}
+
+pub fn create_user(path: &str) -> io::Result<File> {
+    let path = user(path);
+    if let Some(dir) = Path::new(&path).parent() {
+        fs::create_dir_all(dir)?;
+    }
+    File::create(path)
+}
+
+fn text(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+fn is_inside(path: &Path) -> bool {
+    path.components().all(|c| match c {
+        Component::Normal(_) | Component::CurDir => true,
+        _ => false,
+    })
+}
+
+// the archive unpacked under `root`, in a directory named after when it was last changed
+// so a newer archive never mixes with files left over from an older one
+fn unpacked(archive: &Path, root: &Path) -> Result<PathBuf> {
+    let changed = fs::metadata(archive)?
+        .modified()?
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let dir = root.join(changed.to_string());
+    if dir.exists() {
+        return Ok(dir);
+    }
+    if root.exists() {
+        fs::remove_dir_all(root)?;
+    }
+    // only renamed once everything is out, so a run that stops halfway starts over
+    let partial = root.join(format!("{}.partial", changed));
+    fs::create_dir_all(&partial)?;
+    unpack(BufReader::new(File::open(archive)?), &partial)?;
+    fs::rename(&partial, &dir)?;
+    Ok(dir)
+}
+
+// the path from a pax extended header, if it has one
+fn pax_path(records: &[u8]) -> Option<String> {
+    let records = String::from_utf8_lossy(records);
+    let mut rest = &*records;
+    while !rest.is_empty() {
+        let space = rest.find(' ')?;
+        let length: usize = rest[..space].parse().ok()?;
+        if length <= space + 1 || length > rest.len() {
+            return None;
+        }
+        let record = rest[space + 1..length].trim_end_matches('\n');
+        if record.starts_with("path=") {
+            return Some(record["path=".len()..].to_string());
+        }
+        rest = &rest[length..];
+    }
+    None
+}
+
+// only regular files and directories are taken out of the archive; gnu long names and
+// pax paths name the entry after them
+fn unpack<R: Read>(mut reader: R, dir: &Path) -> Result<()> {
+    let mut header = [0; BLOCK];
+    let mut long_name = None;
+    loop {
+        reader.read_exact(&mut header)?;
+        if header.iter().all(|&b| b == 0) {
+            return Ok(());
+        }
+        let short = text(&header[..100]);
+        let magic = &header[257..263];
+        let posix = if magic == b"ustar\0" {
+            true
+        } else if magic == b"ustar " {
+            false
+        } else {
+            bail!("not a ustar archive");
+        };
+        let size = {
+            let size = text(&header[124..136]);
+            u64::from_str_radix(size.trim(), 8)
+                .chain_err(|| format!("bad size for archive entry: {:?}", short))?
+        };
+        let padded = (size + BLOCK as u64 - 1) / BLOCK as u64 * BLOCK as u64;
+        let mut contents = vec![];
+        (&mut reader).take(padded).read_to_end(&mut contents)?;
+        if (contents.len() as u64) < padded {
+            bail!("archive ends in the middle of {:?}", short);
+        }
+        contents.truncate(size as usize);
+
+        match header[156] {
+            b'L' => {
+                long_name = Some(text(&contents));
+                continue;
+            }
+            b'x' => {
+                long_name = pax_path(&contents).or(long_name);
+                continue;
+            }
+            _ => {}
+        }
+        let name = match long_name.take() {
+            Some(n) => PathBuf::from(n),
+            None if posix => Path::new(&text(&header[345..500])).join(short),
+            None => PathBuf::from(short),
+        };
+        if !is_inside(&name) {
+            bail!("archive entry is outside of it: {:?}", name);
+        }
+
+        let path = dir.join(&name);
+        match header[156] {
+            0 | b'0' => {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(path, contents)?;
+            }
+            b'5' => fs::create_dir_all(path)?,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::process;
+
+    const POSIX: &'static [u8] = b"ustar\0";
+    const GNU: &'static [u8] = b"ustar ";
+
+    // an empty directory of its own for each test
+    fn scratch(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("{}_{}_{}", APPLICATION, process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn entry(name: &str, kind: u8, contents: &[u8], magic: &[u8]) -> Vec<u8> {
+        let mut entry = vec![0; BLOCK];
+        entry[..name.len()].copy_from_slice(name.as_bytes());
+        entry[124..135].copy_from_slice(format!("{:011o}", contents.len()).as_bytes());
+        entry[156] = kind;
+        entry[257..263].copy_from_slice(magic);
+        entry.extend_from_slice(contents);
+        let padding = (BLOCK - contents.len() % BLOCK) % BLOCK;
+        entry.extend(vec![0; padding]);
+        entry
+    }
+
+    fn archive(entries: &[Vec<u8>]) -> Vec<u8> {
+        let mut archive = entries.concat();
+        archive.extend(vec![0; 2 * BLOCK]);
+        archive
+    }
+
+    fn pax(key: &str, value: &str) -> Vec<u8> {
+        let body = format!(" {}={}\n", key, value);
+        // the length counts its own digits
+        let mut length = body.len() + 1;
+        while length.to_string().len() + body.len() != length {
+            length += 1;
+        }
+        format!("{}{}", length, body).into_bytes()
+    }
+
+    fn read(path: PathBuf) -> String {
+        String::from_utf8(fs::read(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn unpacks_files_and_directories() {
+        let dir = scratch("files");
+        let tar = archive(&[
+            entry("sprites/", b'5', b"", POSIX),
+            entry("sprites/duck.png", b'0', b"quack", POSIX),
+            entry("game_data.yaml", 0, b"duck: {}", POSIX),
+        ]);
+        unpack(&tar[..], &dir).unwrap();
+        assert!(dir.join("sprites").is_dir());
+        assert_eq!(read(dir.join("sprites/duck.png")), "quack");
+        assert_eq!(read(dir.join("game_data.yaml")), "duck: {}");
+    }
+
+    #[test]
+    fn joins_the_posix_prefix() {
+        let dir = scratch("prefix");
+        let mut file = entry("level.yaml", b'0', b"cats: []", POSIX);
+        file[345..351].copy_from_slice(b"levels");
+        unpack(&archive(&[file])[..], &dir).unwrap();
+        assert_eq!(read(dir.join("levels/level.yaml")), "cats: []");
+    }
+
+    #[test]
+    fn gnu_long_names_name_the_next_entry() {
+        let dir = scratch("gnu");
+        let long = format!("{}/duck.png", "a".repeat(120));
+        let tar = archive(&[
+            entry("././@LongLink", b'L', format!("{}\0", long).as_bytes(), GNU),
+            entry(&long[..100], b'0', b"quack", GNU),
+        ]);
+        unpack(&tar[..], &dir).unwrap();
+        assert_eq!(read(dir.join(long)), "quack");
+    }
+
+    #[test]
+    fn pax_paths_name_the_next_entry() {
+        let dir = scratch("pax");
+        let long = format!("{}/husky.png", "b".repeat(120));
+        let records = [pax("mtime", "1"), pax("path", &long)].concat();
+        let tar = archive(&[
+            entry("PaxHeaders/husky.png", b'x', &records, POSIX),
+            entry(&long[..100], b'0', b"woof", POSIX),
+        ]);
+        unpack(&tar[..], &dir).unwrap();
+        assert_eq!(read(dir.join(long)), "woof");
+    }
+
+    #[test]
+    fn rejects_archives_without_the_magic() {
+        let dir = scratch("magic");
+        let tar = archive(&[entry("duck.png", b'0', b"quack", b"\0\0\0\0\0\0")]);
+        assert!(unpack(&tar[..], &dir).is_err());
+    }
+
+    #[test]
+    fn rejects_entries_outside_of_it() {
+        let dir = scratch("outside");
+        let tar = archive(&[entry("../duck.png", b'0', b"quack", POSIX)]);
+        assert!(unpack(&tar[..], &dir).is_err());
+        assert!(!dir.join("../duck.png").exists());
+    }
+
+    #[test]
+    fn rejects_archives_that_end_early() {
+        let dir = scratch("short");
+        let mut tar = entry("duck.png", b'0', b"quack", POSIX);
+        tar.truncate(BLOCK + 2);
+        assert!(unpack(&tar[..], &dir).is_err());
+    }
+
+    #[test]
+    fn unpacks_once_and_drops_older_archives() {
+        let dir = scratch("unpacked");
+        let tar = dir.join(ARCHIVE);
+        fs::write(&tar, archive(&[entry("duck.png", b'0', b"quack", POSIX)])).unwrap();
+        let root = dir.join("unpacked");
+        let stale = root.join("1");
+        fs::create_dir_all(&stale).unwrap();
+
+        let media = unpacked(&tar, &root).unwrap();
+        assert_eq!(read(media.join("duck.png")), "quack");
+        assert!(!stale.exists());
+
+        // the same archive is not unpacked again
+        fs::remove_file(media.join("duck.png")).unwrap();
+        assert_eq!(unpacked(&tar, &root).unwrap(), media);
+        assert!(!media.join("duck.png").exists());
+    }
+
+    fn place(dir: &Path, what: &str) -> PathBuf {
+        let path = dir.join(what);
+        if what == ARCHIVE {
+            fs::write(&path, b"").unwrap();
+        } else {
+            fs::create_dir_all(&path).unwrap();
+        }
+        path
+    }
+
+    fn arg(path: &Path) -> Option<String> {
+        Some(path.to_string_lossy().into_owned())
+    }
+
+    #[test]
+    fn the_flag_comes_first() {
+        let dir = scratch("flag");
+        let flag = place(&dir, "flag");
+        let env = place(&dir, "env");
+        let exe = place(&dir, "exe");
+        place(&exe, DIR);
+        let found = choose(arg(&flag), arg(&env), Some(&exe), &dir).unwrap();
+        assert_eq!(found, flag);
+    }
+
+    #[test]
+    fn the_environment_comes_before_the_executable() {
+        let dir = scratch("env");
+        let env = place(&dir, "env");
+        let exe = place(&dir, "exe");
+        place(&exe, DIR);
+        let found = choose(None, arg(&env), Some(&exe), &dir).unwrap();
+        assert_eq!(found, env);
+    }
+
+    #[test]
+    fn a_missing_flag_or_environment_is_an_error() {
+        let dir = scratch("missing");
+        let missing = dir.join("missing");
+        place(&dir, DIR);
+        assert!(choose(arg(&missing), None, None, &dir).is_err());
+        assert!(choose(None, arg(&missing), None, &dir).is_err());
+    }
+
+    #[test]
+    fn the_executable_comes_before_the_working_directory() {
+        let dir = scratch("exe");
+        let exe = place(&dir, "exe");
+        let archive = place(&exe, ARCHIVE);
+        place(&dir, DIR);
+        assert_eq!(choose(None, None, Some(&exe), &dir).unwrap(), archive);
+    }
+
+    #[test]
+    fn a_directory_comes_before_an_archive() {
+        let dir = scratch("both");
+        let media = place(&dir, DIR);
+        place(&dir, ARCHIVE);
+        assert_eq!(choose(None, None, None, &dir).unwrap(), media);
+    }
+
+    #[test]
+    fn falls_back_to_the_working_directory() {
+        let dir = scratch("fallback");
+        let exe = place(&dir, "exe");
+        assert_eq!(choose(None, None, Some(&exe), &dir).unwrap(), dir.join(DIR));
+    }
+}
@@ -0,0 +1,56 @@
+use errors::*;
+
+use serde_yaml;
+
+use std::fs::File;
+use std::path::Path;
+
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub enum Unlock {
+    Always,
+    Previous,
+    TotalScore(u32),
+}
+
+impl Default for Unlock {
+    fn default() -> Self {
+        Unlock::Previous
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct LevelEntry {
+    pub name: String,
+    pub file: String,
+    #[serde(default)] pub unlock: Unlock,
+}
+
+impl LevelEntry {
+    pub fn path(&self) -> String {
+        super::media(&self.file)
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Campaign {
+    pub levels: Vec<LevelEntry>,
+}
+
+impl Campaign {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let f = File::open(path)?;
+        let campaign: Campaign = serde_yaml::from_reader(&f)?;
+        if campaign.levels.is_empty() {
+            bail!("campaign does not contain any levels");
+        }
+        Ok(campaign)
+    }
+
+    pub fn next(&self, level: usize) -> Option<usize> {
+        if level + 1 < self.levels.len() {
+            Some(level + 1)
+        } else {
+            None
+        }
+    }
+}
@@ -1,37 +1,74 @@
 use errors::*;
-use super::Dimension;
+use super::{Dimension, Layer, PowerUpKind};
 
+use glm;
 use serde_yaml;
 use moho::renderer::{TextureLoader, TextureManager};
 
+use std::cmp;
 use std::fs::File;
+use std::path::Path;
 use std::rc::Rc;
 
-#[derive(Debug, Deserialize, Clone, Copy)]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
 pub enum GroundKind {
     Top,
     Middle,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub enum CatKind {
     Idle,
+    // walks back and forth over at most that many tiles
     Moving(u32),
+    // walks until it runs into a wall or gets to an edge, then turns around
+    Walking,
+    // hops in place, `height` tiles high every `interval` milliseconds
+    Jumping { height: u32, interval: u64 },
+    // goes after a player within `range` tiles, never straying further than that from home
+    Chasing { range: u32 },
+    // flies back and forth over `distance` tiles, bobbing `amplitude` tiles up and down
+    Flying { distance: u32, amplitude: u32 },
 }
 
-#[derive(Debug, Deserialize)]
-pub struct Cat {
+fn default_cat_speed() -> f64 {
+    1.75
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Enemy {
     pub kind: CatKind,
     pub bottom_left: Dimension,
+    // tiles per second for the kinds that walk or fly
+    #[serde(default = "default_cat_speed")] pub speed: f64,
+    // what landing on it is worth; without one it hurts wherever it is touched
+    #[serde(default)] pub stomp: Option<u32>,
+}
+
+impl Enemy {
+    pub fn new(kind: CatKind, bottom_left: Dimension) -> Self {
+        Enemy {
+            kind,
+            bottom_left,
+            speed: default_cat_speed(),
+            stomp: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PowerUpSpot {
+    pub kind: PowerUpKind,
+    pub bottom_left: Dimension,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Obstacle {
     pub count: Dimension,
     pub bottom_left: Dimension,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Spike {
     pub count: u32,
     pub bottom_left: Dimension,
@@ -40,21 +77,105 @@ pub struct Spike {
     #[serde(default)] pub bottom: Option<GroundKind>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+pub enum Motion {
+    // goes back to the first point after the last one
+    Linear,
+    PingPong,
+}
+
+impl Default for Motion {
+    fn default() -> Self {
+        Motion::PingPong
+    }
+}
+
+fn default_speed() -> f64 {
+    2.
+}
+
+// a single row of tiles that follows `path`; one point keeps it still
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Platform {
+    pub count: u32,
+    pub path: Vec<Dimension>,
+    #[serde(default)] pub motion: Motion,
+    // tiles per second
+    #[serde(default = "default_speed")] pub speed: f64,
+    // can be jumped through from below and only landed on from above
+    #[serde(default)] pub one_way: bool,
+}
+
+// columns where the floor is open and falling through kills the player
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Pit {
+    pub left: u32,
+    pub count: u32,
+}
+
+// where levels without a spawn drop the player in, as the top left corner in pixels from
+// the left and up from the bottom of the level
+pub const DEFAULT_SPAWN: (u32, u32) = (150, 320);
+
+// four backgrounds wide and a screen tall
+fn default_dims() -> Dimension {
+    Dimension { x: 301, y: 21 }
+}
+
+fn default_time_limit() -> u64 {
+    110
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Level {
+    // the tile the player drops in from, counted like everything else from the bottom left
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub spawn: Option<Dimension>,
+    // in tiles
+    #[serde(default = "default_dims")] pub dims: Dimension,
+    // in seconds
+    #[serde(default = "default_time_limit")] pub time_limit: u64,
     pub obstacles: Vec<Obstacle>,
     pub goal: Dimension,
     pub gems: Vec<Dimension>,
     pub coins: Vec<Dimension>,
-    pub cats: Vec<Cat>,
+    #[serde(default)] pub power_ups: Vec<PowerUpSpot>,
+    pub cats: Vec<Enemy>,
     pub spikes: Vec<Spike>,
+    #[serde(default)] pub checkpoints: Vec<Dimension>,
+    #[serde(default)] pub pits: Vec<Pit>,
+    #[serde(default)] pub platforms: Vec<Platform>,
+    // replaces the game's own layers when there are any
+    #[serde(default)] pub backgrounds: Vec<Layer>,
 }
 
 impl Level {
-    pub fn load(path: &'static str) -> Result<Self> {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
         let f = File::open(path)?;
         serde_yaml::from_reader(&f).map_err(Into::into)
     }
+
+    // in pixels; never shorter than the screen so the floor stays at its bottom
+    pub fn size(&self, tile: Dimension) -> glm::IVec2 {
+        let width = self.dims.x * tile.x;
+        let height = cmp::max(self.dims.y * tile.y, 720);
+        glm::ivec2(width as i32, height as i32)
+    }
+
+    // the tile the feet of a player `height` pixels tall start out in
+    pub fn spawn_tile(&self, tile: Dimension, height: u32) -> Dimension {
+        self.spawn.unwrap_or_else(|| {
+            let (x, y) = DEFAULT_SPAWN;
+            Dimension {
+                x: x / tile.x,
+                y: y.saturating_sub(height) / tile.y,
+            }
+        })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let f = File::create(path)?;
+        serde_yaml::to_writer(&f, self).map_err(Into::into)
+    }
 }
 
 impl GroundKind {
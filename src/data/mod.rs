@@ -1,6 +1,11 @@
+mod assets;
+mod campaign;
 mod level;
 
-pub use self::level::{CatKind, GroundKind, Level, Obstacle};
+pub use self::assets::{create_user, locate, media, open_user, user};
+pub use self::campaign::{Campaign, LevelEntry, Unlock};
+pub use self::level::{CatKind, DEFAULT_SPAWN, Enemy, GroundKind, Level, Motion, Obstacle,
+                      PowerUpSpot, Spike};
 
 use errors::*;
 use moho::animation::{self, animator, TileSheet};
@@ -10,10 +15,11 @@ use glm;
 use serde_yaml;
 
 use std::fs::File;
+use std::path::Path;
 use std::rc::Rc;
 use std::time::Duration;
 
-#[derive(Debug, Deserialize, Clone, Copy)]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
 pub struct Dimension {
     pub x: u32,
     pub y: u32,
@@ -43,17 +49,19 @@ impl Sprite {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Texture(String);
 
 impl Texture {
+    pub fn path(&self) -> String {
+        media(&format!("sprites/{}", self.0))
+    }
+
     pub fn load<'t, TL: TextureLoader<'t>>(
         &self,
         texture_manager: &mut TextureManager<'t, TL>,
     ) -> Result<Rc<TL::Texture>> {
-        texture_manager
-            .load(&format!("media/sprites/{}", self.0))
-            .map_err(Into::into)
+        texture_manager.load(&self.path()).map_err(Into::into)
     }
 }
 
@@ -86,6 +94,35 @@ pub struct Image {
     pub out_size: Dimension,
 }
 
+fn default_scroll() -> f64 {
+    1.
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Layer {
+    pub texture: Texture,
+    pub out_size: Dimension,
+    // how fast it moves with the camera; 1 keeps up with the level and 0 stays on screen
+    #[serde(default = "default_scroll")] pub scroll: f64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Checkpoint {
+    pub idle: Texture,
+    pub active: Texture,
+    pub out_size: Dimension,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub struct Camera {
+    // how far the player moves around the middle of the screen before it follows
+    pub dead_zone: Dimension,
+    // fraction of the way to its target the camera covers each sixtieth of a second
+    pub damping: f64,
+    // how far ahead of the player the camera looks in the direction it is facing
+    pub look_ahead: u32,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Collectable {
     pub animation: Sprite,
@@ -93,6 +130,79 @@ pub struct Collectable {
     pub score: u32,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub enum PowerUpKind {
+    DoubleJump,
+    SpeedBoost,
+    Invincibility,
+    // adds its duration to the clock instead of lasting for it
+    ExtraTime,
+    // pulls in the coins around the player
+    Magnet,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PowerUp {
+    pub kind: PowerUpKind,
+    pub pickup: Collectable,
+    // shown in the HUD while it lasts
+    pub icon: Texture,
+    // in milliseconds
+    pub duration: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AudioFile(String);
+
+impl AudioFile {
+    pub fn path(&self) -> String {
+        media(&format!("audio/{}", self.0))
+    }
+}
+
+// anything left out stays silent
+#[derive(Debug, Deserialize, Default)]
+pub struct Music {
+    pub menu: Option<AudioFile>,
+    pub game_play: Option<AudioFile>,
+    pub finish: Option<AudioFile>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct Sounds {
+    pub jump: Option<AudioFile>,
+    pub coin: Option<AudioFile>,
+    pub gem: Option<AudioFile>,
+    pub damage: Option<AudioFile>,
+    pub cat: Option<AudioFile>,
+    pub navigate: Option<AudioFile>,
+    pub complete: Option<AudioFile>,
+}
+
+fn full_volume() -> u8 {
+    100
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Audio {
+    #[serde(default)] pub music: Music,
+    #[serde(default)] pub sounds: Sounds,
+    // from 0 for silent to 100 for as loud as the file was recorded
+    #[serde(default = "full_volume")] pub music_volume: u8,
+    #[serde(default = "full_volume")] pub effects_volume: u8,
+}
+
+impl Default for Audio {
+    fn default() -> Self {
+        Audio {
+            music: Music::default(),
+            sounds: Sounds::default(),
+            music_volume: full_volume(),
+            effects_volume: full_volume(),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Ground {
     pub center: Texture,
@@ -111,15 +221,21 @@ pub struct Game {
     pub ground: Ground,
     pub gem: Collectable,
     pub coin: Collectable,
+    #[serde(default)] pub power_ups: Vec<PowerUp>,
     pub cat: Cat,
-    pub background: Image,
+    // drawn back to front
+    pub backgrounds: Vec<Layer>,
     pub goal: Image,
     pub heart: Image,
     pub spike: Image,
+    pub checkpoint: Checkpoint,
+    pub camera: Camera,
+    // files go in the audio directory of the data; the game is silent without any
+    #[serde(default)] pub audio: Audio,
 }
 
 impl Game {
-    pub fn load(path: &'static str) -> Result<Game> {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Game> {
         let f = File::open(path)?;
         serde_yaml::from_reader(&f).map_err(Into::into)
     }
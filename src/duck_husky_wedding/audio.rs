@@ -0,0 +1,249 @@
+use data;
+
+use sdl2::AudioSubsystem;
+#[cfg(feature = "audio")]
+use sdl2::mixer::{self, Channel, Chunk, Sdl2MixerContext};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sound {
+    Jump,
+    Coin,
+    Gem,
+    Damage,
+    Cat,
+    Navigate,
+    Complete,
+}
+
+#[cfg(feature = "audio")]
+static SOUNDS: [Sound; 7] = [
+    Sound::Jump,
+    Sound::Coin,
+    Sound::Gem,
+    Sound::Damage,
+    Sound::Cat,
+    Sound::Navigate,
+    Sound::Complete,
+];
+
+#[cfg(feature = "audio")]
+impl Sound {
+    fn file(self, sounds: &data::Sounds) -> Option<&data::AudioFile> {
+        match self {
+            Sound::Jump => sounds.jump.as_ref(),
+            Sound::Coin => sounds.coin.as_ref(),
+            Sound::Gem => sounds.gem.as_ref(),
+            Sound::Damage => sounds.damage.as_ref(),
+            Sound::Cat => sounds.cat.as_ref(),
+            Sound::Navigate => sounds.navigate.as_ref(),
+            Sound::Complete => sounds.complete.as_ref(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Music {
+    Menu,
+    GamePlay,
+    Finish,
+}
+
+#[cfg(feature = "audio")]
+static MUSIC: [Music; 3] = [Music::Menu, Music::GamePlay, Music::Finish];
+
+#[cfg(feature = "audio")]
+impl Music {
+    fn file(self, music: &data::Music) -> Option<&data::AudioFile> {
+        match self {
+            Music::Menu => music.menu.as_ref(),
+            Music::GamePlay => music.game_play.as_ref(),
+            Music::Finish => music.finish.as_ref(),
+        }
+    }
+}
+
+#[cfg(feature = "audio")]
+const CHANNELS: i32 = 16;
+#[cfg(feature = "audio")]
+const CHUNK_SIZE: i32 = 1024;
+#[cfg(feature = "audio")]
+const MUSIC_FADE: i32 = 500;
+
+// an opened audio device, ready to load sounds into
+#[cfg(feature = "audio")]
+pub struct Mixer {
+    _subsystem: AudioSubsystem,
+    _context: Sdl2MixerContext,
+}
+
+// never opened when the game is built without the audio feature
+#[cfg(not(feature = "audio"))]
+pub enum Mixer {}
+
+#[cfg(not(feature = "audio"))]
+impl Mixer {
+    pub fn open(_subsystem: AudioSubsystem) -> Result<Self, String> {
+        Err(String::from("the game was built without the audio feature"))
+    }
+}
+
+#[cfg(feature = "audio")]
+impl Mixer {
+    // fails when there is no device to play on, as with SDL's dummy driver
+    pub fn open(subsystem: AudioSubsystem) -> Result<Self, String> {
+        if subsystem.current_audio_driver() == "dummy" {
+            return Err(String::from("there is no audio device"));
+        }
+        let context = mixer::init(mixer::INIT_OGG)?;
+        mixer::open_audio(
+            mixer::DEFAULT_FREQUENCY,
+            mixer::DEFAULT_FORMAT,
+            mixer::DEFAULT_CHANNELS,
+            CHUNK_SIZE,
+        )?;
+        mixer::allocate_channels(CHANNELS);
+        Ok(Mixer {
+            _subsystem: subsystem,
+            _context: context,
+        })
+    }
+}
+
+#[cfg(feature = "audio")]
+impl Drop for Mixer {
+    fn drop(&mut self) {
+        mixer::close_audio();
+    }
+}
+
+// files that fail to load stay silent instead of stopping the game; why is added to `failed`
+#[cfg(feature = "audio")]
+fn load<A, F>(file: Option<&data::AudioFile>, loader: F, failed: &mut Vec<String>) -> Option<A>
+where
+    F: FnOnce(String) -> Result<A, String>,
+{
+    let path = file?.path();
+    match loader(path.clone()) {
+        Ok(a) => Some(a),
+        Err(e) => {
+            failed.push(format!("{:?} ({})", path, e));
+            None
+        }
+    }
+}
+
+// `mix` comes from the game data and `setting` from the player, both from 0 to 100
+#[cfg(feature = "audio")]
+fn volume(mix: u8, setting: u8) -> i32 {
+    i32::from(mix.min(100)) * i32::from(setting.min(100)) * mixer::MAX_VOLUME / 10_000
+}
+
+#[cfg(feature = "audio")]
+pub struct Loaded {
+    sounds: Vec<(Sound, Option<Chunk>)>,
+    music: Vec<(Music, Option<mixer::Music<'static>>)>,
+    playing: Option<Music>,
+    music_mix: u8,
+    effects_mix: u8,
+    // dropped last so the device outlives everything played on it
+    _mixer: Mixer,
+}
+
+pub enum Audio {
+    #[cfg(feature = "audio")]
+    Mixer(Loaded),
+    // plays nothing; used when there is no audio device
+    Null,
+}
+
+#[cfg(not(feature = "audio"))]
+impl Audio {
+    pub fn load(mixer: Option<Mixer>, _data: &data::Audio) -> Self {
+        match mixer {
+            Some(m) => match m {},
+            None => Audio::Null,
+        }
+    }
+
+    pub fn set_volumes(&mut self, _music: u8, _effects: u8) {}
+
+    pub fn play(&self, _sound: Sound) {}
+
+    pub fn play_music(&mut self, _music: Music) {}
+}
+
+#[cfg(feature = "audio")]
+impl Audio {
+    pub fn load(mixer: Option<Mixer>, data: &data::Audio) -> Self {
+        let mixer = match mixer {
+            Some(m) => m,
+            None => return Audio::Null,
+        };
+        let mut failed = vec![];
+        let sounds = SOUNDS
+            .iter()
+            .map(|&s| (s, load(s.file(&data.sounds), Chunk::from_file, &mut failed)))
+            .collect();
+        let music = MUSIC
+            .iter()
+            .map(|&m| {
+                let track = load(m.file(&data.music), mixer::Music::from_file, &mut failed);
+                (m, track)
+            })
+            .collect();
+        if !failed.is_empty() {
+            println!("audio that could not be loaded stays silent: {}", failed.join(", "));
+        }
+        let mut audio = Audio::Mixer(Loaded {
+            sounds,
+            music,
+            playing: None,
+            music_mix: data.music_volume,
+            effects_mix: data.effects_volume,
+            _mixer: mixer,
+        });
+        audio.set_volumes(100, 100);
+        audio
+    }
+
+    pub fn set_volumes(&mut self, music: u8, effects: u8) {
+        if let Audio::Mixer(ref loaded) = *self {
+            mixer::Music::set_volume(volume(loaded.music_mix, music));
+            Channel::all().set_volume(volume(loaded.effects_mix, effects));
+        }
+    }
+
+    pub fn play(&self, sound: Sound) {
+        if let Audio::Mixer(ref loaded) = *self {
+            let chunk = loaded
+                .sounds
+                .iter()
+                .find(|&&(s, _)| s == sound)
+                .and_then(|&(_, ref c)| c.as_ref());
+            if let Some(c) = chunk {
+                // running out of free channels only drops the sound
+                let _ = Channel::all().play(c, 0);
+            }
+        }
+    }
+
+    // keeps going if `music` is already playing
+    pub fn play_music(&mut self, music: Music) {
+        if let Audio::Mixer(ref mut loaded) = *self {
+            if loaded.playing == Some(music) {
+                return;
+            }
+            loaded.playing = Some(music);
+            mixer::Music::halt();
+            let track = loaded
+                .music
+                .iter()
+                .find(|&&(m, _)| m == music)
+                .and_then(|&(_, ref t)| t.as_ref());
+            if let Some(t) = track {
+                // music that will not play leaves the game silent, like a missing file
+                let _ = t.fade_in(-1, MUSIC_FADE);
+            }
+        }
+    }
+}
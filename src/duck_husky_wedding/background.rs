@@ -4,20 +4,37 @@ use data;
 
 use glm;
 use moho;
-use moho::renderer::{options, Renderer, Scene, TextureLoader, TextureManager};
+use moho::renderer::{options, Renderer, TextureLoader, TextureManager};
 
 use std::rc::Rc;
 
-pub struct Background<T> {
+struct Layer<T> {
     texture: Rc<T>,
-    dimensions: glm::UVec2,
+    dimensions: glm::IVec2,
+    scroll: f64,
 }
 
-impl<T> Clone for Background<T> {
+impl<T> Clone for Layer<T> {
     fn clone(&self) -> Self {
-        Background {
+        Layer {
             texture: Rc::clone(&self.texture),
             dimensions: self.dimensions,
+            scroll: self.scroll,
+        }
+    }
+}
+
+pub struct Background<T> {
+    layers: Vec<Layer<T>>,
+    // of the whole level; the layers line up with its bottom when the camera is there
+    level: glm::IVec2,
+}
+
+impl<T> Clone for Background<T> {
+    fn clone(&self) -> Self {
+        Background {
+            layers: self.layers.clone(),
+            level: self.level,
         }
     }
 }
@@ -25,32 +42,58 @@ impl<T> Clone for Background<T> {
 impl<T> Background<T> {
     pub fn load<'t, TL>(
         texture_manager: &mut TextureManager<'t, TL>,
-        data: &data::Image,
+        layers: &[data::Layer],
+        level: glm::IVec2,
     ) -> Result<Self>
     where
         TL: TextureLoader<'t, Texture = T>,
     {
-        let texture = data.texture.load(texture_manager)?;
-        let dimensions = data.out_size.into();
-        Ok(Background {
-            texture,
-            dimensions,
-        })
+        let layers = layers
+            .iter()
+            .map(|l| -> Result<Layer<T>> {
+                Ok(Layer {
+                    texture: l.texture.load(texture_manager)?,
+                    dimensions: l.out_size.into(),
+                    scroll: l.scroll,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Background { layers, level })
     }
-}
 
-impl<'t, R: Renderer<'t>> Scene<R> for Background<R::Texture> {
-    fn show(&self, renderer: &mut R) -> moho::errors::Result<()> {
-        (0..4)
-            .map(|i| {
-                glm::ivec4(
-                    self.dimensions.x as i32 * i,
-                    0,
-                    self.dimensions.x as i32,
-                    self.dimensions.y as i32,
-                )
+    // `view` is the part of the level on screen
+    pub fn show<'t, R>(&self, renderer: &mut R, view: &glm::IVec4) -> moho::errors::Result<()>
+    where
+        R: Renderer<'t, Texture = T>,
+    {
+        let bottom_view = self.level.y - view.w;
+        self.layers
+            .iter()
+            .map(|l| {
+                // how far the layer lags behind the camera
+                let lag = glm::dvec2(
+                    f64::from(view.x) * (1. - l.scroll),
+                    f64::from(view.y - bottom_view) * (1. - l.scroll),
+                );
+                let origin = glm::ivec2(lag.x as i32, self.level.y + lag.y as i32);
+                let dims = l.dimensions;
+                // never negative while the scroll stays between 0 and 1
+                let first = (view.x - origin.x) / dims.x;
+                let last = (view.x + view.z - origin.x) / dims.x;
+                let rows = (origin.y - view.y + dims.y - 1) / dims.y;
+                (first..last + 1)
+                    .flat_map(|i| (1..rows + 1).map(move |j| (i, j)))
+                    .map(|(i, j)| {
+                        let dst = glm::ivec4(
+                            origin.x + dims.x * i,
+                            origin.y - dims.y * j,
+                            dims.x,
+                            dims.y,
+                        );
+                        renderer.copy(&*l.texture, options::at(dst))
+                    })
+                    .try()
             })
-            .map(|d| renderer.copy(&*self.texture, options::at(d)))
             .try()
     }
 }
@@ -0,0 +1,205 @@
+use data;
+
+use glm;
+
+use std::f64::consts::PI;
+use std::time::Duration;
+
+// how an enemy wants to get around; the level has the final say on where it ends up
+#[derive(Clone, Copy)]
+pub enum Behaviour {
+    Idle,
+    Patrol {
+        // how far it walks before turning back; without one only walls and edges turn it
+        total: Option<f64>,
+        current: f64,
+        left: bool,
+    },
+    Jumping {
+        height: f64,
+        interval: Duration,
+        wait: Duration,
+    },
+    Chasing {
+        range: f64,
+        // how far right of home it is
+        current: f64,
+        left: bool,
+    },
+    Flying {
+        total: f64,
+        current: f64,
+        amplitude: f64,
+        left: bool,
+    },
+}
+
+// what an enemy is after during a single update
+pub struct Intent {
+    pub step: glm::DVec2,
+    // how high to jump, in pixels, if it wants to
+    pub leap: Option<f64>,
+}
+
+impl Behaviour {
+    pub fn load(kind: &data::CatKind, tile: glm::DVec2) -> Self {
+        match *kind {
+            data::CatKind::Idle => Behaviour::Idle,
+            data::CatKind::Moving(t) => Behaviour::Patrol {
+                total: Some(f64::from(t) * tile.x),
+                current: 0.,
+                left: false,
+            },
+            data::CatKind::Walking => Behaviour::Patrol {
+                total: None,
+                current: 0.,
+                left: false,
+            },
+            data::CatKind::Jumping { height, interval } => Behaviour::Jumping {
+                height: f64::from(height) * tile.y,
+                interval: Duration::from_millis(interval),
+                wait: Duration::from_millis(interval),
+            },
+            data::CatKind::Chasing { range } => Behaviour::Chasing {
+                range: f64::from(range) * tile.x,
+                current: 0.,
+                left: true,
+            },
+            data::CatKind::Flying {
+                distance,
+                amplitude,
+            } => Behaviour::Flying {
+                total: f64::from(distance) * tile.x,
+                current: 0.,
+                amplitude: f64::from(amplitude) * tile.y,
+                left: false,
+            },
+        }
+    }
+
+    // `speed` is in pixels per second and `target` is how far right of the enemy the
+    // player is, when there is one to go after
+    pub fn update(
+        &mut self,
+        duration: Duration,
+        speed: f64,
+        target: Option<f64>,
+        on_ground: bool,
+    ) -> Intent {
+        let secs = duration.as_secs() as f64 + f64::from(duration.subsec_nanos()) / 1e9;
+        let step = speed * secs;
+        let mut intent = Intent {
+            step: glm::dvec2(0., 0.),
+            leap: None,
+        };
+        match *self {
+            Behaviour::Idle => {}
+            Behaviour::Patrol {
+                total,
+                current,
+                ref mut left,
+            } => {
+                let next = if *left { current - step } else { current + step };
+                if total.map_or(false, |t| next < 0. || next > t) {
+                    *left = !*left;
+                } else {
+                    intent.step.x = next - current;
+                }
+            }
+            Behaviour::Jumping {
+                height,
+                interval,
+                ref mut wait,
+            } => if on_ground {
+                *wait = match wait.checked_sub(duration) {
+                    Some(w) if w > Duration::default() => w,
+                    _ => {
+                        intent.leap = Some(height);
+                        interval
+                    }
+                }
+            },
+            Behaviour::Chasing {
+                range,
+                current,
+                ref mut left,
+            } => if let Some(t) = target {
+                if t.abs() <= range {
+                    *left = t < 0.;
+                    // never strays further than `range` from home
+                    let next = current + t.signum() * step.min(t.abs());
+                    intent.step.x = next.max(-range).min(range) - current;
+                }
+            },
+            Behaviour::Flying {
+                total,
+                current,
+                amplitude,
+                ref mut left,
+            } => {
+                let next = if *left { current - step } else { current + step };
+                if next < 0. || next > total {
+                    *left = !*left;
+                } else {
+                    // one full wave over the whole distance, going up first
+                    let height = |c: f64| if total > 0. {
+                        -amplitude * (2. * PI * c / total).sin()
+                    } else {
+                        0.
+                    };
+                    intent.step = glm::dvec2(next - current, height(next) - height(current));
+                }
+            }
+        }
+        intent
+    }
+
+    // how far right it really moved once the level had its say
+    pub fn moved(&mut self, x: f64) {
+        match *self {
+            Behaviour::Patrol {
+                ref mut current, ..
+            }
+            | Behaviour::Chasing {
+                ref mut current, ..
+            }
+            | Behaviour::Flying {
+                ref mut current, ..
+            } => *current += x,
+            _ => {}
+        }
+    }
+
+    // it ran into a wall or got to an edge
+    pub fn turn(&mut self) {
+        match *self {
+            Behaviour::Patrol { ref mut left, .. } | Behaviour::Flying { ref mut left, .. } => {
+                *left = !*left
+            }
+            _ => {}
+        }
+    }
+
+    pub fn is_left(&self) -> bool {
+        match *self {
+            Behaviour::Idle | Behaviour::Jumping { .. } => true,
+            Behaviour::Patrol { left, .. }
+            | Behaviour::Chasing { left, .. }
+            | Behaviour::Flying { left, .. } => left,
+        }
+    }
+
+    pub fn is_still(&self) -> bool {
+        match *self {
+            Behaviour::Idle | Behaviour::Jumping { .. } => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_flying(&self) -> bool {
+        match *self {
+            Behaviour::Flying { .. } => true,
+            _ => false,
+        }
+    }
+}
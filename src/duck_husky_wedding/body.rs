@@ -76,6 +76,25 @@ impl Body {
             })
     }
 
+    // lowest point of any of the shapes
+    pub fn bottom(&self) -> f64 {
+        let rects = self.rectangles.iter().map(|r| r.top_left.y + r.dims.y);
+        let circles = self.circles.iter().map(|c| c.center.y + c.radius);
+        rects.chain(circles).fold(::std::f64::MIN, f64::max)
+    }
+
+    pub fn left(&self) -> f64 {
+        let rects = self.rectangles.iter().map(|r| r.top_left.x);
+        let circles = self.circles.iter().map(|c| c.center.x - c.radius);
+        rects.chain(circles).fold(::std::f64::MAX, f64::min)
+    }
+
+    pub fn right(&self) -> f64 {
+        let rects = self.rectangles.iter().map(|r| r.top_left.x + r.dims.x);
+        let circles = self.circles.iter().map(|c| c.center.x + c.radius);
+        rects.chain(circles).fold(::std::f64::MIN, f64::max)
+    }
+
     pub fn collides(&self, other: &Body) -> bool {
         other.rectangles.iter().any(|r| self.intersects(r))
             || other.circles.iter().any(|c| self.intersects(c))
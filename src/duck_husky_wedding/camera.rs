@@ -1,9 +1,12 @@
+use data;
+
 use glm;
 use moho;
 use moho::renderer::{ColorRGBA, Options, Renderer, Texture};
 use sdl2::rect;
 
 use std::cmp;
+use std::time::Duration;
 
 pub struct Camera<'c, R: 'c> {
     viewport: &'c ViewPort,
@@ -26,8 +29,37 @@ impl ViewPort {
         self.translation = self.translation + t;
     }
 
-    pub fn center(&mut self, center: glm::IVec2) {
-        self.translation.x = cmp::max(center.x - self.dims.x / 2, 0);
+    pub fn translation(&self) -> glm::IVec2 {
+        self.translation
+    }
+
+    // the part of the level on screen
+    pub fn view(&self) -> glm::IVec4 {
+        glm::ivec4(
+            self.translation.x,
+            self.translation.y,
+            self.dims.x,
+            self.dims.y,
+        )
+    }
+
+    // keeps the view inside a level of the given size on all four sides
+    pub fn center(&mut self, center: glm::IVec2, bounds: glm::IVec2) {
+        let tl = center - self.dims / 2;
+        let max = bounds - self.dims;
+        self.translation.x = cmp::max(cmp::min(tl.x, max.x), 0);
+        self.translation.y = cmp::max(cmp::min(tl.y, max.y), 0);
+    }
+
+    // a copy somewhere between `previous` and where it is now, for drawing in between
+    // updates
+    pub fn blend(&self, previous: glm::IVec2, alpha: f64) -> ViewPort {
+        let from = glm::to_dvec2(previous);
+        let to = glm::to_dvec2(self.translation);
+        ViewPort {
+            dims: self.dims,
+            translation: glm::to_ivec2(from + (to - from) * alpha),
+        }
     }
 
     pub fn contains(&self, rect: &glm::IVec4) -> bool {
@@ -44,6 +76,72 @@ impl ViewPort {
     }
 }
 
+// eases a viewport after a target instead of snapping to it
+pub struct Follow {
+    settings: data::Camera,
+    bounds: glm::IVec2,
+    focus: glm::DVec2,
+}
+
+impl Follow {
+    pub fn new(settings: data::Camera, bounds: glm::IVec2) -> Self {
+        Follow {
+            settings,
+            bounds,
+            focus: glm::dvec2(0., 0.),
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        target: glm::DVec2,
+        backwards: bool,
+        delta: Duration,
+        viewport: &mut ViewPort,
+    ) {
+        let wanted = self.ahead_of(target, backwards);
+        let half = glm::DVec2::from(self.settings.dead_zone) / 2.;
+        let past = |offset: f64, half: f64| {
+            if offset > half {
+                offset - half
+            } else if offset < -half {
+                offset + half
+            } else {
+                0.
+            }
+        };
+        let offset = wanted - self.focus;
+        let chase = glm::dvec2(past(offset.x, half.x), past(offset.y, half.y));
+        // the damping is per sixtieth of a second whatever the update rate
+        let secs = delta.as_secs() as f64 + f64::from(delta.subsec_nanos()) / 1e9;
+        let damping = 1. - (1. - self.settings.damping).powf(secs * 60.);
+        self.focus = self.focus + chase * damping;
+        viewport.center(glm::to_ivec2(self.focus), self.bounds);
+    }
+
+    // keeps looking where it was, e.g. when the game data is loaded again
+    pub fn reset(&mut self, settings: data::Camera, bounds: glm::IVec2) {
+        self.settings = settings;
+        self.bounds = bounds;
+    }
+
+    // jumps straight to the target, e.g. after a respawn
+    pub fn snap(&mut self, target: glm::DVec2, backwards: bool, viewport: &mut ViewPort) {
+        self.focus = self.ahead_of(target, backwards);
+        viewport.center(glm::to_ivec2(self.focus), self.bounds);
+    }
+
+    fn ahead_of(&self, target: glm::DVec2, backwards: bool) -> glm::DVec2 {
+        let look_ahead = f64::from(self.settings.look_ahead);
+        let x = if backwards {
+            target.x - look_ahead
+        } else {
+            target.x + look_ahead
+        };
+        glm::dvec2(x, target.y)
+    }
+}
+
 impl<'c, 't, R: Renderer<'t>> Renderer<'t> for Camera<'c, R>
 where
     R::Texture: Texture,
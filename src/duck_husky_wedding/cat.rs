@@ -1,5 +1,6 @@
 use data;
 use errors::*;
+use duck_husky_wedding::behaviour::Behaviour;
 use duck_husky_wedding::body::Body;
 
 use glm;
@@ -9,27 +10,24 @@ use moho::renderer::{options, Renderer, Scene, Texture, TextureLoader, TextureMa
 
 use std::time::Duration;
 
-#[derive(Clone, Copy)]
-pub enum Kind {
-    Idle,
-    Moving {
-        total: u32,
-        current: u32,
-        left: bool,
-    },
-}
+// in pixels per second, and per second squared for gravity, like the player
+const GRAVITY: f64 = 2700.;
+const MAX_FALL: f64 = 1500.;
 
 pub struct Data<T> {
     dst: glm::DVec4,
     body: Vec<data::Shape>,
     animation: animation::Data<T>,
-    kind: Kind,
+    behaviour: Behaviour,
+    speed: f64,
+    stomp: Option<u32>,
 }
 
 impl<T> Data<T> {
     pub fn load<'t, TL>(
         bl: glm::IVec2,
-        kind: Kind,
+        tile: glm::IVec2,
+        enemy: &data::Enemy,
         data: &data::Cat,
         texture_manager: &mut TextureManager<'t, TL>,
     ) -> Result<Self>
@@ -42,9 +40,12 @@ impl<T> Data<T> {
             let bl = glm::to_dvec2(bl);
             glm::dvec4(bl.x, bl.y - dims.y + 4., dims.x, dims.y)
         };
-        let animation = match kind {
-            Kind::Idle => &data.idle,
-            Kind::Moving { .. } => &data.walking,
+        let tile = glm::to_dvec2(tile);
+        let behaviour = Behaviour::load(&enemy.kind, tile);
+        let animation = if behaviour.is_still() {
+            &data.idle
+        } else {
+            &data.walking
         };
         let animation = animation.load(texture_manager)?;
         let body = data.body.clone();
@@ -52,16 +53,27 @@ impl<T> Data<T> {
             dst,
             body,
             animation,
-            kind,
+            behaviour,
+            speed: enemy.speed * tile.x,
+            stomp: enemy.stomp,
         })
     }
 }
 
 pub struct Cat<T> {
     pub dst: glm::DVec4,
+    // where it was before this update, to draw it in between the two
+    previous: glm::DVec4,
+    blend: f64,
     body: Vec<data::Shape>,
     animation: Animation<T>,
-    kind: Kind,
+    behaviour: Behaviour,
+    speed: f64,
+    // pixels per second, negative while going up
+    fall: f64,
+    on_ground: bool,
+    // what landing on it is worth, if it can be landed on at all
+    pub stomp: Option<u32>,
 }
 
 impl<T> Cat<T> {
@@ -69,63 +81,92 @@ impl<T> Cat<T> {
         Cat {
             body: data.body.clone(),
             dst: data.dst,
+            previous: data.dst,
+            blend: 1.,
             animation: data.animation.clone().start(),
-            kind: data.kind,
+            behaviour: data.behaviour,
+            speed: data.speed,
+            fall: 0.,
+            on_ground: false,
+            stomp: data.stomp,
         }
     }
 
-    pub fn update(&mut self, duration: Duration) {
+    // where it would like to move this update, gravity included; `player` is the center
+    // of whoever it may be after
+    pub fn step(&mut self, duration: Duration, player: Option<glm::DVec2>) -> glm::DVec2 {
         self.animation.animate(duration);
-        if let Kind::Moving {
-            total,
-            mut current,
-            mut left,
-        } = self.kind
-        {
-            let step = 1;
-            let updated = if left {
-                if current < step {
-                    left = false;
-                    step - current
-                } else {
-                    current - step
-                }
-            } else if current + step > total {
-                left = true;
-                2 * total - current - step
-            } else {
-                current + step
-            };
-            self.dst.x += f64::from(updated) - f64::from(current);
-            current = updated;
-
-            self.kind = Kind::Moving {
-                total,
-                current,
-                left,
-            };
+        let target = player.map(|p| p.x - self.dst.x - self.dst.z / 2.);
+        let intent = self.behaviour.update(duration, self.speed, target, self.on_ground);
+        let mut step = intent.step;
+        if !self.behaviour.is_flying() {
+            let secs = duration.as_secs() as f64 + f64::from(duration.subsec_nanos()) / 1e9;
+            if let Some(height) = intent.leap {
+                self.fall = -(2. * GRAVITY * height).sqrt();
+            }
+            self.fall = (self.fall + GRAVITY * secs).min(MAX_FALL);
+            step.y += self.fall * secs;
+        }
+        step
+    }
+
+    // walking cats stop at edges instead of stepping off them
+    pub fn keeps_footing(&self) -> bool {
+        self.on_ground && !self.behaviour.is_flying()
+    }
+
+    pub fn turn(&mut self) {
+        self.behaviour.turn();
+    }
+
+    // moves by `step` once `force` has pushed it out of whatever it ran into
+    pub fn settle(&mut self, step: glm::DVec2, force: glm::DVec2) {
+        self.dst.x += step.x + force.x;
+        self.dst.y += step.y + force.y;
+        self.behaviour.moved(step.x + force.x);
+        self.on_ground = force.y < 0.;
+        if (force.y < 0. && self.fall > 0.) || (force.y > 0. && self.fall < 0.) {
+            self.fall = 0.;
+        }
+        if force.x * step.x < 0. {
+            self.behaviour.turn();
         }
     }
 
+    pub fn remember(&mut self) {
+        self.previous = self.dst;
+    }
+
+    // takes on the look of whichever of `all` moves like it does, keeping its feet where
+    // they are
+    pub fn refresh(&mut self, all: &[Data<T>]) {
+        let still = self.behaviour.is_still();
+        if let Some(d) = all.iter().find(|d| d.behaviour.is_still() == still) {
+            self.animation = d.animation.clone().start();
+            self.body = d.body.clone();
+            self.dst.y += self.dst.w - d.dst.w;
+            self.dst.z = d.dst.z;
+            self.dst.w = d.dst.w;
+            self.previous = self.dst;
+        }
+    }
+
+    pub fn interpolate(&mut self, alpha: f64) {
+        self.blend = alpha;
+    }
+
     pub fn body(&self) -> Body {
-        let backwards = match self.kind {
-            Kind::Idle | Kind::Moving { left: true, .. } => true,
-            _ => false,
-        };
-        Body::new(&self.dst, &self.body, backwards)
+        Body::new(&self.dst, &self.body, self.behaviour.is_left())
     }
 }
 
 impl<'t, R: Renderer<'t>> Scene<R> for Cat<R::Texture> {
     fn show(&self, renderer: &mut R) -> moho::errors::Result<()> {
-        let dst_rect = glm::to_ivec4(self.dst);
+        let dst_rect = glm::to_ivec4(self.previous + (self.dst - self.previous) * self.blend);
         let mut options = options::at(dst_rect);
 
-        match self.kind {
-            Kind::Idle | Kind::Moving { left: true, .. } => {
-                options = options.flip(options::Flip::Horizontal)
-            }
-            _ => {}
+        if self.behaviour.is_left() {
+            options = options.flip(options::Flip::Horizontal)
         }
 
         renderer.copy_asset(&self.animation.tile(), options)?;
@@ -0,0 +1,67 @@
+use data;
+use errors::*;
+
+use glm;
+use moho;
+use moho::renderer::{options, Renderer, Scene, Texture, TextureLoader, TextureManager};
+
+use std::rc::Rc;
+
+pub struct Checkpoint<T> {
+    idle: Rc<T>,
+    active: Rc<T>,
+    pub dst: glm::IVec4,
+    pub reached: bool,
+}
+
+impl<T> Clone for Checkpoint<T> {
+    fn clone(&self) -> Self {
+        Checkpoint {
+            idle: Rc::clone(&self.idle),
+            active: Rc::clone(&self.active),
+            dst: self.dst,
+            reached: self.reached,
+        }
+    }
+}
+
+impl<T: Texture> Checkpoint<T> {
+    pub fn load<'t, TL>(
+        bl: glm::IVec2,
+        data: &data::Checkpoint,
+        texture_manager: &mut TextureManager<'t, TL>,
+    ) -> Result<Self>
+    where
+        TL: TextureLoader<'t, Texture = T>,
+    {
+        let idle = data.idle.load(texture_manager)?;
+        let active = data.active.load(texture_manager)?;
+        let dims: glm::IVec2 = data.out_size.into();
+        let dst = glm::ivec4(bl.x, bl.y - dims.y, dims.x, dims.y);
+        Ok(Checkpoint {
+            idle,
+            active,
+            dst,
+            reached: false,
+        })
+    }
+}
+
+impl<T> Checkpoint<T> {
+    // where a player of the given height stands when respawning here
+    pub fn spawn(&self, height: f64) -> glm::DVec2 {
+        let bottom = f64::from(self.dst.y + self.dst.w);
+        glm::dvec2(f64::from(self.dst.x), bottom - height)
+    }
+}
+
+impl<'t, R: Renderer<'t>> Scene<R> for Checkpoint<R::Texture> {
+    fn show(&self, renderer: &mut R) -> moho::errors::Result<()> {
+        let texture = if self.reached {
+            &*self.active
+        } else {
+            &*self.idle
+        };
+        renderer.copy(texture, options::at(self.dst))
+    }
+}
@@ -10,11 +10,22 @@ use glm;
 
 use std::time::Duration;
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    Coin,
+    Gem,
+    PowerUp(data::PowerUpKind),
+}
+
 #[derive(Debug)]
 pub struct Collectable<T> {
     animation: Animation<T>,
     pub body: Rectangle,
     pub score: u32,
+    pub kind: Kind,
+    // where it was before this update, to draw it in between the two
+    previous: glm::DVec2,
+    blend: f64,
 }
 
 #[derive(Debug)]
@@ -22,11 +33,13 @@ pub struct Data<T> {
     animation: animation::Data<T>,
     body: Rectangle,
     score: u32,
+    kind: Kind,
 }
 
 impl<T: Texture> Data<T> {
     pub fn load<'t, TL>(
         bl: glm::IVec2,
+        kind: Kind,
         data: &data::Collectable,
         texture_manager: &mut TextureManager<'t, TL>,
     ) -> Result<Self>
@@ -45,6 +58,7 @@ impl<T: Texture> Data<T> {
             animation,
             body,
             score: data.score,
+            kind,
         })
     }
 }
@@ -55,19 +69,53 @@ impl<T> Collectable<T> {
             animation: data.animation.clone().start(),
             body: data.body.clone(),
             score: data.score,
+            kind: data.kind,
+            previous: data.body.top_left,
+            blend: 1.,
+        }
+    }
+
+    pub fn remember(&mut self) {
+        self.previous = self.body.top_left;
+    }
+
+    pub fn interpolate(&mut self, alpha: f64) {
+        self.blend = alpha;
+    }
+
+    // takes on the look and worth of whichever of `all` is the same kind, keeping its feet
+    // where they are
+    pub fn refresh(&mut self, all: &[Data<T>]) {
+        if let Some(d) = all.iter().find(|d| d.kind == self.kind) {
+            self.animation = d.animation.clone().start();
+            self.body.top_left.y += self.body.dims.y - d.body.dims.y;
+            self.body.dims = d.body.dims;
+            self.previous = self.body.top_left;
+            self.score = d.score;
         }
     }
 
     pub fn animate(&mut self, duration: Duration) {
         self.animation.animate(duration);
     }
+
+    // moves it up to `step` pixels towards `target`
+    pub fn pull(&mut self, target: glm::DVec2, step: f64) {
+        let center = self.body.top_left + self.body.dims / 2.;
+        let to_target = target - center;
+        let distance = glm::length(to_target);
+        if distance > 0. {
+            self.body.top_left = self.body.top_left + to_target * (step.min(distance) / distance);
+        }
+    }
 }
 
 impl<'t, R: Renderer<'t>> Scene<R> for Collectable<R::Texture> {
     fn show(&self, renderer: &mut R) -> moho::errors::Result<()> {
+        let tl = self.previous + (self.body.top_left - self.previous) * self.blend;
         let dst = glm::ivec4(
-            self.body.top_left.x as i32,
-            self.body.top_left.y as i32,
+            tl.x as i32,
+            tl.y as i32,
             self.body.dims.x as i32,
             self.body.dims.y as i32,
         );
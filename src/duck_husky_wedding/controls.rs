@@ -0,0 +1,235 @@
+use data;
+use errors::*;
+
+use moho::input;
+use sdl2::keyboard::Keycode;
+use serde_yaml;
+
+use std::collections::BTreeMap;
+
+const FILE: &'static str = "controls.yaml";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    Jump,
+    MoveDown,
+    Confirm,
+    Back,
+    Pause,
+}
+
+// the index is the bit used in a snapshot
+pub static ACTIONS: [Action; 7] = [
+    Action::MoveLeft,
+    Action::MoveRight,
+    Action::Jump,
+    Action::MoveDown,
+    Action::Confirm,
+    Action::Back,
+    Action::Pause,
+];
+
+impl Action {
+    pub fn label(&self) -> &'static str {
+        match *self {
+            Action::MoveLeft => "Move Left",
+            Action::MoveRight => "Move Right",
+            Action::Jump => "Jump / Up",
+            Action::MoveDown => "Down",
+            Action::Confirm => "Confirm",
+            Action::Back => "Back",
+            Action::Pause => "Pause",
+        }
+    }
+
+    fn default_key(&self) -> Keycode {
+        match *self {
+            Action::MoveLeft => Keycode::Left,
+            Action::MoveRight => Keycode::Right,
+            Action::Jump => Keycode::Up,
+            Action::MoveDown => Keycode::Down,
+            Action::Confirm => Keycode::Return,
+            Action::Back => Keycode::Escape,
+            Action::Pause => Keycode::Escape,
+        }
+    }
+
+    pub fn bit(&self) -> u8 {
+        1 << *self as u8
+    }
+}
+
+// the actions held down and the ones newly pressed during a single update,
+// plus how far an analog stick is pushed sideways in percent
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub struct Snapshot(u8, u8, i8);
+
+impl Snapshot {
+    pub fn capture(state: &input::State, bindings: &Bindings) -> Self {
+        ACTIONS
+            .iter()
+            .fold(Snapshot::default(), |Snapshot(down, pressed, stick), a| {
+                let keys = bindings.keys(*a);
+                let bit = a.bit();
+                Snapshot(
+                    if keys.iter().any(|&k| state.is_key_down(k)) {
+                        down | bit
+                    } else {
+                        down
+                    },
+                    if keys.iter().any(|&k| state.did_press_key(k)) {
+                        pressed | bit
+                    } else {
+                        pressed
+                    },
+                    stick,
+                )
+            })
+    }
+
+    pub fn from_actions(down: &[Action], pressed: &[Action]) -> Self {
+        let bits = |actions: &[Action]| actions.iter().fold(0, |acc, a| acc | a.bit());
+        Snapshot(bits(down), bits(pressed), 0)
+    }
+
+    pub fn from_bits(down: u8, pressed: u8, stick: i8) -> Self {
+        Snapshot(down, pressed, stick)
+    }
+
+    pub fn combine(self, other: Snapshot) -> Self {
+        let stick = if self.2 != 0 { self.2 } else { other.2 };
+        Snapshot(self.0 | other.0, self.1 | other.1, stick)
+    }
+
+    // -1 to 1; the stick wins over digital input when it is pushed
+    pub fn horizontal(&self) -> f64 {
+        if self.2 != 0 {
+            return f64::from(self.2) / 100.;
+        }
+        match (self.is_down(Action::MoveLeft), self.is_down(Action::MoveRight)) {
+            (true, false) => -1.,
+            (false, true) => 1.,
+            _ => 0.,
+        }
+    }
+
+    pub fn is_down(&self, action: Action) -> bool {
+        self.0 & action.bit() != 0
+    }
+
+    pub fn did_press(&self, action: Action) -> bool {
+        self.1 & action.bit() != 0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Bindings {
+    keys: BTreeMap<Action, Vec<Keycode>>,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        let keys = ACTIONS
+            .iter()
+            .map(|a| (*a, vec![a.default_key()]))
+            .collect();
+        Bindings { keys }
+    }
+}
+
+impl Bindings {
+    pub fn keys(&self, action: Action) -> &[Keycode] {
+        self.keys.get(&action).map_or(&[][..], Vec::as_slice)
+    }
+
+    pub fn bind(&mut self, action: Action, key: Keycode) {
+        self.keys.insert(action, vec![key]);
+    }
+}
+
+// keys are kept by their sdl name so the file can be edited by hand
+type Stored = BTreeMap<Action, Vec<String>>;
+
+pub fn get() -> Bindings {
+    let stored: Result<Stored> = data::open_user(FILE)
+        .chain_err(|| "")
+        .and_then(|f| serde_yaml::from_reader(f).map_err(Into::into));
+    let mut bindings = Bindings::default();
+    for (action, names) in stored.unwrap_or_default() {
+        let keys: Vec<_> = names.iter().filter_map(|n| Keycode::from_name(n)).collect();
+        if !keys.is_empty() {
+            bindings.keys.insert(action, keys);
+        }
+    }
+    bindings
+}
+
+pub fn create(bindings: &Bindings) -> Result<()> {
+    let stored: Stored = bindings
+        .keys
+        .iter()
+        .map(|(a, keys)| (*a, keys.iter().map(|k| k.name()).collect()))
+        .collect();
+    let file = data::create_user(FILE)?;
+    serde_yaml::to_writer(file, &stored).map_err(Into::into)
+}
+
+// keys offered when rebinding from the controls screen
+pub static BINDABLE: [Keycode; 54] = [
+    Keycode::Left,
+    Keycode::Right,
+    Keycode::Up,
+    Keycode::Down,
+    Keycode::Space,
+    Keycode::Return,
+    Keycode::Escape,
+    Keycode::Backspace,
+    Keycode::Tab,
+    Keycode::LShift,
+    Keycode::RShift,
+    Keycode::LCtrl,
+    Keycode::RCtrl,
+    Keycode::LAlt,
+    Keycode::RAlt,
+    Keycode::Comma,
+    Keycode::Period,
+    Keycode::Semicolon,
+    Keycode::A,
+    Keycode::B,
+    Keycode::C,
+    Keycode::D,
+    Keycode::E,
+    Keycode::F,
+    Keycode::G,
+    Keycode::H,
+    Keycode::I,
+    Keycode::J,
+    Keycode::K,
+    Keycode::L,
+    Keycode::M,
+    Keycode::N,
+    Keycode::O,
+    Keycode::P,
+    Keycode::Q,
+    Keycode::R,
+    Keycode::S,
+    Keycode::T,
+    Keycode::U,
+    Keycode::V,
+    Keycode::W,
+    Keycode::X,
+    Keycode::Y,
+    Keycode::Z,
+    Keycode::Num0,
+    Keycode::Num1,
+    Keycode::Num2,
+    Keycode::Num3,
+    Keycode::Num4,
+    Keycode::Num5,
+    Keycode::Num6,
+    Keycode::Num7,
+    Keycode::Num8,
+    Keycode::Num9,
+];
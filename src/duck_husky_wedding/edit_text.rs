@@ -1,12 +1,12 @@
+use duck_husky_wedding::controls::{Action, Snapshot};
 use duck_husky_wedding::hud::{AsCached, CacheValue, TextCache};
 use duck_husky_wedding::flicker::Flicker;
 use errors::*;
 use utils::Try;
 
 use glm;
-use moho::{self, input};
+use moho;
 use moho::renderer::{options, ColorRGBA, Font, Renderer, Scene, Texture};
-use sdl2::keyboard::Keycode;
 
 use std::cmp;
 use std::rc::Rc;
@@ -59,21 +59,21 @@ impl<T, F: Font<Texture = T>> EditText<T, F> {
         })
     }
 
-    pub fn update(&mut self, elapsed: Duration, input: &input::State) {
-        if input.did_press_key(Keycode::Left) {
+    pub fn update(&mut self, elapsed: Duration, actions: &Snapshot) {
+        if actions.did_press(Action::MoveLeft) {
             self.move_left();
         }
-        if input.did_press_key(Keycode::Right) {
+        if actions.did_press(Action::MoveRight) {
             self.move_right();
         }
-        if input.did_press_key(Keycode::Up) {
+        if actions.did_press(Action::Jump) {
             self.values[self.active] = match self.values[self.active] {
                 None => Some('a'),
                 Some('z') => None,
                 Some(c) => Some((c as u8 + 1) as char),
             };
         }
-        if input.did_press_key(Keycode::Down) {
+        if actions.did_press(Action::MoveDown) {
             self.values[self.active] = match self.values[self.active] {
                 None => Some('z'),
                 Some('a') => None,
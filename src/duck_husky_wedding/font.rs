@@ -3,6 +3,8 @@ use errors::*;
 use moho::renderer::font;
 use moho::renderer::{FontLoader, FontManager};
 
+use data;
+
 use std::rc::Rc;
 
 #[derive(Clone, Copy)]
@@ -12,30 +14,64 @@ pub enum Kind {
 }
 
 impl Kind {
-    pub fn path(&self) -> &'static str {
+    fn file(&self) -> &'static str {
         match *self {
-            Kind::KenPixel => "media/fonts/kenpixel_mini.ttf",
-            Kind::Joystix => "media/fonts/joystix.monospace.ttf",
+            Kind::KenPixel => "fonts/kenpixel_mini.ttf",
+            Kind::Joystix => "fonts/joystix.monospace.ttf",
         }
     }
 }
 
+// where the fonts are, worked out once the data directory is known
+pub struct Paths([String; 2]);
+
+impl Paths {
+    pub fn new() -> Self {
+        Paths([
+            data::media(Kind::KenPixel.file()),
+            data::media(Kind::Joystix.file()),
+        ])
+    }
+
+    fn get(&self, kind: Kind) -> &str {
+        &self.0[kind as usize]
+    }
+}
+
 pub trait Manager: Sized {
     type Font;
 
     fn load(&mut self, kind: Kind, size: u16) -> Result<Rc<Self::Font>>;
 }
 
-impl<'f, FL> Manager for FontManager<'f, FL>
+// loads fonts by kind from the paths it was given
+pub struct Fonts<'f, FL>
+where
+    FL: 'f + FontLoader<'f>,
+{
+    manager: FontManager<'f, FL>,
+    paths: &'f Paths,
+}
+
+impl<'f, FL: FontLoader<'f>> Fonts<'f, FL> {
+    pub fn new(loader: &'f FL, paths: &'f Paths) -> Self {
+        Fonts {
+            manager: FontManager::new(loader),
+            paths,
+        }
+    }
+}
+
+impl<'f, FL> Manager for Fonts<'f, FL>
 where
     FL: FontLoader<'f>,
 {
     type Font = FL::Font;
 
     fn load(&mut self, kind: Kind, size: u16) -> Result<Rc<FL::Font>> {
-        self.load(&font::Details {
-            path: kind.path(),
-            size,
-        }).chain_err(|| format!("cannot load font in path: {:?}", kind.path()))
+        let path = self.paths.get(kind);
+        self.manager
+            .load(&font::Details { path, size })
+            .chain_err(|| format!("cannot load font in path: {:?}", path))
     }
 }
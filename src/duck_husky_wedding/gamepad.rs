@@ -0,0 +1,123 @@
+use duck_husky_wedding::controls::{Action, Snapshot};
+
+use sdl2::GameControllerSubsystem;
+use sdl2::controller::{Axis, Button, GameController};
+
+use std::cmp;
+
+// a stick counts as pushed past DEADZONE but only lets go once back under RELEASE, so one
+// resting near the edge does not keep pressing
+const DEADZONE: i16 = 8000;
+const RELEASE: i16 = 6000;
+
+static BUTTONS: [(Button, Action); 7] = [
+    (Button::DPadLeft, Action::MoveLeft),
+    (Button::DPadRight, Action::MoveRight),
+    (Button::DPadUp, Action::Jump),
+    (Button::DPadDown, Action::MoveDown),
+    (Button::A, Action::Confirm),
+    (Button::B, Action::Back),
+    (Button::Start, Action::Pause),
+];
+
+pub struct Gamepads {
+    subsystem: GameControllerSubsystem,
+    controllers: Vec<GameController>,
+    joysticks: u32,
+    held: u8,
+}
+
+impl Gamepads {
+    pub fn new(subsystem: GameControllerSubsystem) -> Self {
+        Gamepads {
+            subsystem,
+            controllers: vec![],
+            joysticks: 0,
+            held: 0,
+        }
+    }
+
+    // menus and name entry read jump as up, so A only jumps while `playing`
+    pub fn update(&mut self, playing: bool) -> Snapshot {
+        self.refresh();
+
+        let mut down = 0;
+        let mut jump = 0;
+        let mut stick = 0;
+        for c in &self.controllers {
+            if c.button(Button::A) {
+                jump = Action::Jump.bit();
+            }
+            down = BUTTONS
+                .iter()
+                .filter(|&&(b, _)| c.button(b))
+                .fold(down, |acc, &(_, a)| acc | a.bit());
+
+            let x = cmp::max(c.axis(Axis::LeftX), -i16::max_value());
+            let side = self.direction(x, Action::MoveLeft, Action::MoveRight);
+            if side != 0 {
+                down |= side;
+                if stick == 0 {
+                    stick = Self::scale(x);
+                }
+            }
+            // pushing up only means up in menus; jumping stays on the buttons
+            let y = cmp::max(c.axis(Axis::LeftY), -i16::max_value());
+            down |= match self.direction(y, Action::Jump, Action::MoveDown) {
+                b if b == Action::Jump.bit() && playing => 0,
+                b => b,
+            };
+        }
+
+        // still held when play starts, the A that confirmed a menu is no jump
+        let all = down | jump;
+        let pressed = all & !self.held;
+        self.held = all;
+        if playing {
+            Snapshot::from_bits(all, pressed, stick)
+        } else {
+            Snapshot::from_bits(down, pressed & down, stick)
+        }
+    }
+
+    // reopens every controller whenever one is plugged in or pulled out
+    fn refresh(&mut self) {
+        let joysticks = self.subsystem.num_joysticks().unwrap_or(0);
+        if joysticks == self.joysticks && self.controllers.iter().all(|c| c.attached()) {
+            return;
+        }
+        self.joysticks = joysticks;
+        let subsystem = &self.subsystem;
+        self.controllers = (0..joysticks)
+            .filter(|&i| subsystem.is_game_controller(i))
+            .filter_map(|i| subsystem.open(i).ok())
+            .collect();
+    }
+
+    // the bit of `negative` or `positive` for where a stick axis is pushed, or 0
+    fn direction(&self, value: i16, negative: Action, positive: Action) -> u8 {
+        let action = if value < 0 { negative } else { positive };
+        let threshold = if self.held & action.bit() != 0 {
+            RELEASE
+        } else {
+            DEADZONE
+        };
+        if value.abs() > threshold {
+            action.bit()
+        } else {
+            0
+        }
+    }
+
+    // maps the stick past where it lets go onto -100..100
+    fn scale(x: i16) -> i8 {
+        let range = f64::from(i16::max_value() - RELEASE);
+        let past = f64::from(x.abs() - RELEASE).max(0.).min(range);
+        let percent = (past / range * 100.) as i8;
+        if x < 0 {
+            -percent
+        } else {
+            percent
+        }
+    }
+}
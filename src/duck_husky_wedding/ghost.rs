@@ -0,0 +1,158 @@
+use data;
+use duck_husky_wedding::screen::PlayerKind;
+use errors::*;
+
+use glm;
+use moho;
+use moho::animation::{self, Animation};
+use moho::renderer::{options, Renderer, Scene, Texture, TextureLoader, TextureManager};
+use serde_yaml;
+
+use std::path::Path;
+use std::rc::Rc;
+use std::time::Duration;
+
+// how opaque the ghost is drawn, out of 255, so it reads as a shadow of a run
+const ALPHA: u8 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum Pose {
+    Standing,
+    Jumping,
+    Moving,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub struct Frame {
+    pub dst: [f64; 4],
+    pub backwards: bool,
+    pub pose: Pose,
+    // how far into its running animation the player was; older runs did not keep it
+    #[serde(default)]
+    pub stride: Option<Duration>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Run {
+    pub score: u32,
+    pub frames: Vec<Frame>,
+}
+
+impl Run {
+    pub fn load(level: &str, player: PlayerKind) -> Option<Self> {
+        data::open_user(&Self::file(level, player))
+            .ok()
+            .and_then(|f| serde_yaml::from_reader(f).ok())
+    }
+
+    // keeps whichever of this run and the stored one scored higher
+    pub fn save_if_best(&self, level: &str, player: PlayerKind) -> Result<()> {
+        if let Some(best) = Self::load(level, player) {
+            if best.score >= self.score {
+                return Ok(());
+            }
+        }
+        let file = data::create_user(&Self::file(level, player))?;
+        serde_yaml::to_writer(file, self).map_err(Into::into)
+    }
+
+    fn file(level: &str, player: PlayerKind) -> String {
+        let level = Path::new(level)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or(level);
+        format!("ghosts/{}_{:?}.yaml", level, player)
+    }
+}
+
+pub struct Ghost<T> {
+    frames: Vec<Frame>,
+    next: usize,
+    // what `next` was before this update, to draw in between the two frames
+    previous: usize,
+    blend: f64,
+    animation: animation::Data<T>,
+    moving: Option<Animation<T>>,
+    texture: Rc<T>,
+}
+
+impl<T> Ghost<T> {
+    pub fn load<'t, TL>(
+        data: &data::Player,
+        run: Run,
+        texture_manager: &mut TextureManager<'t, TL>,
+    ) -> Result<Self>
+    where
+        T: Texture,
+        TL: TextureLoader<'t, Texture = T>,
+    {
+        let animation = data.animation.load(texture_manager)?;
+        let texture = data.idle_texture.load(texture_manager)?;
+        Ok(Ghost {
+            frames: run.frames,
+            next: 0,
+            previous: 0,
+            blend: 1.,
+            moving: None,
+            animation,
+            texture,
+        })
+    }
+
+    pub fn update(&mut self, delta: Duration) {
+        self.previous = self.next;
+
+        // once the run is over the ghost waits at its last frame
+        let frame = match self.frames.get(self.next) {
+            None => return,
+            Some(f) => *f,
+        };
+        let was_moving = self.next > 0 && self.frames[self.next - 1].pose == Pose::Moving;
+        self.next += 1;
+
+        self.moving = match (frame.pose, frame.stride, self.moving.take()) {
+            (Pose::Moving, Some(stride), _) => {
+                let mut a = self.animation.clone().start();
+                a.animate(stride);
+                Some(a)
+            }
+            (Pose::Moving, None, Some(mut a)) if was_moving => {
+                a.animate(delta);
+                Some(a)
+            }
+            (Pose::Moving, None, _) => Some(self.animation.clone().start()),
+            _ => None,
+        };
+    }
+}
+
+impl<T> Ghost<T> {
+    pub fn interpolate(&mut self, alpha: f64) {
+        self.blend = alpha;
+    }
+}
+
+impl<'t, R: Renderer<'t>> Scene<R> for Ghost<R::Texture> {
+    fn show(&self, renderer: &mut R) -> moho::errors::Result<()> {
+        if self.next == 0 {
+            return Ok(());
+        }
+
+        let frame = &self.frames[self.next - 1];
+        let from = if self.previous > 0 {
+            &self.frames[self.previous - 1]
+        } else {
+            frame
+        };
+        let blend = |i: usize| (from.dst[i] + (frame.dst[i] - from.dst[i]) * self.blend) as i32;
+        let dst = glm::ivec4(blend(0), blend(1), blend(2), blend(3));
+        let mut options = options::at(dst).alpha(ALPHA);
+        if frame.backwards {
+            options = options.flip(options::Flip::Horizontal);
+        }
+        match self.moving {
+            Some(ref a) => renderer.copy_asset(&a.tile(), options),
+            None => renderer.copy(&*self.texture, options),
+        }
+    }
+}
@@ -1,25 +1,140 @@
+use data;
+use duck_husky_wedding::screen::PlayerKind;
 use errors::*;
 
 use serde_yaml;
 
-use std::fs::File;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-const PATH: &'static str = "media/high_scores.yaml";
+const FILE: &'static str = "high_scores.yaml";
+const TABLE_SIZE: usize = 10;
+// the only level that existed when scores were kept in a single list
+const LEGACY_LEVEL: &'static str = "level.yaml";
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ScoreEntry {
     pub score: u32,
     pub name: String,
+    #[serde(default)] pub time: Duration,
+    #[serde(default)] pub date: u64,
 }
 
-pub fn get() -> Vec<ScoreEntry> {
-    File::open(PATH)
+impl ScoreEntry {
+    pub fn new(name: String, score: u32, time: Duration) -> Self {
+        let date = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        ScoreEntry {
+            name,
+            score,
+            time,
+            date,
+        }
+    }
+
+    pub fn formatted_date(&self) -> String {
+        // days since epoch to civil date; see http://howardhinnant.github.io/date_algorithms.html
+        let z = (self.date / 86_400) as i64 + 719_468;
+        let era = z / 146_097;
+        let doe = z - era * 146_097;
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+        format!("{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Table {
+    pub level: String,
+    pub player: PlayerKind,
+    pub entries: Vec<ScoreEntry>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct HighScores {
+    tables: Vec<Table>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Stored {
+    Tables(HighScores),
+    Legacy(Vec<ScoreEntry>),
+}
+
+impl HighScores {
+    pub fn table(&self, level: &str, player: PlayerKind) -> &[ScoreEntry] {
+        self.tables
+            .iter()
+            .find(|t| t.level == level && t.player == player)
+            .map(|t| t.entries.as_slice())
+            .unwrap_or(&[])
+    }
+
+    pub fn qualifies(&self, level: &str, player: PlayerKind, score: u32) -> bool {
+        let table = self.table(level, player);
+        table.len() < TABLE_SIZE || table.iter().any(|s| s.score <= score)
+    }
+
+    pub fn insert(&mut self, level: &str, player: PlayerKind, entry: ScoreEntry) {
+        let index = match self.tables
+            .iter()
+            .position(|t| t.level == level && t.player == player)
+        {
+            Some(i) => i,
+            None => {
+                self.tables.push(Table {
+                    level: level.to_owned(),
+                    player,
+                    entries: vec![],
+                });
+                self.tables.len() - 1
+            }
+        };
+        let entries = &mut self.tables[index].entries;
+        entries.push(entry);
+        entries.sort_by(|a, b| a.score.cmp(&b.score).reverse());
+        entries.truncate(TABLE_SIZE);
+    }
+
+    // the character was never recorded, so the old list goes to the husky, which the player
+    // select started on; the duck's board starts out empty
+    fn migrate(legacy: Vec<ScoreEntry>) -> Self {
+        let table = Table {
+            player: PlayerKind::Husky,
+            level: LEGACY_LEVEL.to_owned(),
+            entries: legacy,
+        };
+        HighScores {
+            tables: vec![table],
+        }
+    }
+}
+
+pub fn get() -> HighScores {
+    let stored: Result<Stored> = data::open_user(FILE)
         .chain_err(|| "")
-        .and_then(|f| serde_yaml::from_reader(f).map_err(Into::into))
-        .unwrap_or_default()
+        .and_then(|f| serde_yaml::from_reader(f).map_err(Into::into));
+
+    match stored {
+        Ok(Stored::Tables(scores)) => scores,
+        Ok(Stored::Legacy(legacy)) => {
+            let scores = HighScores::migrate(legacy);
+            if let Err(e) = create(&scores) {
+                println!("could not migrate high score file: {:?}", e);
+            }
+            scores
+        }
+        Err(_) => HighScores::default(),
+    }
 }
 
-pub fn create(entries: &[ScoreEntry]) -> Result<()> {
-    let file = File::create(PATH)?;
-    serde_yaml::to_writer(file, entries).map_err(Into::into)
+pub fn create(scores: &HighScores) -> Result<()> {
+    let file = data::create_user(FILE)?;
+    serde_yaml::to_writer(file, scores).map_err(Into::into)
 }
@@ -1,39 +1,83 @@
+pub mod audio;
 pub mod camera;
 pub mod world;
 mod background;
+mod behaviour;
 mod high_score;
 mod body;
 mod button;
 mod cat;
+mod checkpoint;
 mod collectable;
+mod controls;
 mod edit_text;
 mod flicker;
-mod font;
+pub mod font;
+pub mod gamepad;
+mod ghost;
 mod goal;
 mod hud;
 mod npc;
 mod obstacle;
+mod platform;
 mod player;
+mod power_up;
+mod progress;
+pub mod reload;
+pub mod replay;
 mod screen;
+pub mod settings;
 
 use errors::*;
 use data;
+use self::audio::{Audio, Mixer};
+use self::controls::{self, Snapshot};
+use self::font::Fonts;
+use self::world::Data as WorldData;
+use self::gamepad::Gamepads;
+use self::reload::{Changes, Report, Watcher};
+use self::settings::Display;
 
+use glm;
 use moho::input;
-use moho::renderer::{Canvas, ColorRGBA, Font, FontLoader, FontManager, Texture, TextureLoader,
-                     TextureManager};
+use moho::renderer::{Canvas, ColorRGBA, Font, FontLoader, Texture, TextureLoader, TextureManager};
 use moho::timer::Timer;
+use sdl2::keyboard::Keycode;
 
+use std::rc::Rc;
 use std::time::Duration;
 
+const GAME_SPEED: u32 = 60;
+const MAX_SKIP: u32 = 10;
+// headless runs give up once this many updates pass without an outcome
+const MAX_SIMULATED: u32 = 60 * 60 * GAME_SPEED;
+
+pub use self::player::jump_arc;
+pub use self::screen::{Outcome, PlayerKind};
+
+// what the game shows first
+pub enum Launch {
+    Menu,
+    // the first campaign level when none is given; the character is picked when not given
+    Play {
+        level: Option<String>,
+        player: Option<PlayerKind>,
+    },
+    Replay(String),
+}
+
 pub struct DuckHuskyWedding<'f, 't, TL, FL, R, E>
 where
     TL: 't + TextureLoader<'t>,
     FL: 'f + FontLoader<'f>,
 {
     input_manager: input::Manager<E>,
+    gamepads: Option<Gamepads>,
+    mixer: Option<Mixer>,
+    // kept to start over with a fresh texture manager when the sprites change
+    texture_loader: &'t TL,
     texture_manager: TextureManager<'t, TL>,
-    font_manager: FontManager<'f, FL>,
+    font_manager: Fonts<'f, FL>,
     renderer: R,
 }
 
@@ -47,35 +91,82 @@ where
     pub fn new(
         renderer: R,
         font_loader: &'f FL,
+        font_paths: &'f font::Paths,
         texture_loader: &'t TL,
         input_manager: input::Manager<E>,
+        gamepads: Option<Gamepads>,
+        mixer: Option<Mixer>,
     ) -> Self {
         let texture_manager = TextureManager::new(texture_loader);
-        let font_manager = FontManager::new(font_loader);
+        let font_manager = Fonts::new(font_loader, font_paths);
         DuckHuskyWedding {
             input_manager,
+            gamepads,
+            mixer,
+            texture_loader,
             texture_manager,
             font_manager,
             renderer,
         }
     }
 
-    pub fn run(&mut self) -> Result<()>
+    pub fn run(&mut self, launch: Launch) -> Result<()>
     where
-        R: Canvas<'t, Texture = <TL as TextureLoader<'t>>::Texture>,
+        R: Canvas<'t, Texture = <TL as TextureLoader<'t>>::Texture> + Display,
         E: input::EventPump,
     {
-        let game_data = data::Game::load("media/game_data.yaml")?;
-        let level_data = data::Level::load("media/level.yaml")?;
+        let game_data = data::Game::load(data::media("game_data.yaml"))?;
+        let campaign = data::Campaign::load(data::media("campaign.yaml"))?;
+        let start = match launch {
+            Launch::Play { ref level, player } => {
+                let level = match *level {
+                    None => 0,
+                    Some(ref file) => campaign
+                        .levels
+                        .iter()
+                        .position(|l| l.file == *file)
+                        .ok_or_else(|| format!("level {:?} is not in the campaign", file))?,
+                };
+                Some(match player {
+                    None => screen::Kind::PlayerSelect(level),
+                    Some(player) => screen::Kind::GamePlay(screen::Attempt {
+                        player,
+                        level,
+                        ghost: false,
+                    }),
+                })
+            }
+            Launch::Menu | Launch::Replay(_) => None,
+        };
+        let mut watcher = {
+            let mut paths = vec![data::media("game_data.yaml"), data::media("sprites")];
+            paths.extend(campaign.levels.iter().map(|l| l.path()));
+            Watcher::new(paths)
+        };
+        let mut report = Report::default();
+        let mut audio = Audio::load(self.mixer.take(), &game_data.audio);
         let mut screen_manager = screen::Manager::load(
             &mut self.font_manager,
             &mut self.texture_manager,
-            &level_data,
+            campaign,
             game_data,
         )?;
+        // what the window was last set to
+        let mut shown = *screen_manager.settings();
+        audio.set_volumes(shown.music_volume, shown.effects_volume);
+        if let Launch::Replay(path) = launch {
+            let recording = replay::Recording::load(&path)
+                .chain_err(|| format!("cannot load replay in path: {:?}", path))?;
+            screen_manager.replay(recording, &mut self.font_manager, &mut self.texture_manager)?;
+        }
+        if let Some(s) = start {
+            let selected =
+                screen_manager.select_screen(s, &mut self.font_manager, &mut self.texture_manager);
+            if let Err(e) = selected {
+                report = Report::load(&mut self.font_manager, &e)?;
+            }
+        }
 
-        const GAME_SPEED: u32 = 60;
-        const MAX_SKIP: u32 = 10;
         let update_duration = Duration::new(0, 1_000_000_000 / GAME_SPEED);
         let mut timer = Timer::new();
         let mut delta = Duration::default();
@@ -90,27 +181,162 @@ where
                     break 'game_loop;
                 }
 
-                let next_screen = screen_manager.mut_screen().update(update_duration, state);
+                let playing = screen_manager.screen().is_playing();
+                let pad = self.gamepads
+                    .as_mut()
+                    .map_or_else(Snapshot::default, |g| g.update(playing));
+                let next_screen = screen_manager.update(update_duration, state, pad);
+                for &s in screen_manager.screen().sounds() {
+                    audio.play(s);
+                }
+                if let Some(s) = screen_manager.screen().settings() {
+                    audio.set_volumes(s.music_volume, s.effects_volume);
+                    if s.display != shown.display || s.window != shown.window {
+                        report = match self.renderer.apply(s) {
+                            Ok(()) => Report::default(),
+                            Err(e) => Report::load(&mut self.font_manager, &e)?,
+                        };
+                        shown = *s;
+                    }
+                }
                 if let Some(s) = next_screen {
-                    screen_manager.select_screen(
+                    let selected = screen_manager.select_screen(
                         s,
                         &mut self.font_manager,
                         &mut self.texture_manager,
                     );
+                    report = match selected {
+                        Ok(()) => Report::default(),
+                        Err(e) => Report::load(&mut self.font_manager, &e)?,
+                    };
+                }
+                audio.play_music(screen_manager.screen().music());
+                let changed = watcher.poll(update_duration);
+                if !changed.is_empty() {
+                    let changes = Changes::sort(changed);
+                    // the texture manager cannot forget single textures, and sprites are
+                    // the only thing it holds that can go out of date
+                    if changes.sprites {
+                        self.texture_manager = TextureManager::new(self.texture_loader);
+                    }
+                    report = match screen_manager.reload(&mut self.texture_manager, &changes) {
+                        Ok(()) => Report::default(),
+                        Err(e) => Report::load(&mut self.font_manager, &e)?,
+                    };
                 }
 
                 delta -= update_duration;
                 loops += 1;
             }
-            // println!("fps: {:?}", game_time.fps());
-            // let interpolation = delta.subsec_nanos() as f64
-            // / update_duration.subsec_nanos() as f64;
             self.renderer.set_draw_color(color);
             self.renderer.clear();
+            screen_manager
+                .mut_screen()
+                .interpolate(interpolation(delta, update_duration));
             screen_manager.mut_screen().before_draw()?;
             self.renderer.show(screen_manager.screen())?;
+            self.renderer.show(&report)?;
             self.renderer.present();
         }
         Ok(())
     }
 }
+
+// how far the time left over after the last update is into the next one
+fn interpolation(left_over: Duration, update_duration: Duration) -> f64 {
+    let nanos = |d: Duration| d.as_secs() as f64 * 1e9 + f64::from(d.subsec_nanos());
+    (nanos(left_over) / nanos(update_duration)).min(1.)
+}
+
+pub fn simulate<'f, 't, TL, FL, R>(
+    texture_loader: &'t TL,
+    font_loader: &'f FL,
+    font_paths: &'f font::Paths,
+    renderer: &mut R,
+    recording: replay::Recording,
+) -> Result<Outcome>
+where
+    TL: TextureLoader<'t>,
+    TL::Texture: Texture,
+    FL: FontLoader<'f>,
+    FL::Font: Font<Texture = TL::Texture>,
+    R: Canvas<'t, Texture = TL::Texture>,
+{
+    let mut texture_manager = TextureManager::new(texture_loader);
+    let mut font_manager = Fonts::new(font_loader, font_paths);
+    let game_data = data::Game::load(data::media("game_data.yaml"))?;
+    let campaign = data::Campaign::load(data::media("campaign.yaml"))?;
+    let mut game_play =
+        screen::GamePlayData::load(&mut texture_manager, Rc::new(campaign), Rc::new(game_data))?
+            .replay(&mut texture_manager, &mut font_manager, recording)?;
+
+    let update_duration = Duration::new(0, 1_000_000_000 / GAME_SPEED);
+    let mut warned = false;
+    for _ in 0..MAX_SIMULATED {
+        if let Some(outcome) = game_play.simulate(update_duration) {
+            return Ok(outcome);
+        }
+        if !warned && game_play.is_input_exhausted() {
+            println!("input ended before the run did; continuing without input");
+            warned = true;
+        }
+        game_play.before_draw()?;
+        renderer.show(&game_play)?;
+    }
+    bail!("simulation did not finish after {} updates", MAX_SIMULATED)
+}
+
+// runs the level being edited until Escape is pressed or the run ends
+pub fn playtest<'f, 't, TL, FL, R, E>(
+    texture_manager: &mut TextureManager<'t, TL>,
+    font_manager: &mut Fonts<'f, FL>,
+    renderer: &mut R,
+    input_manager: &mut input::Manager<E>,
+    game: Rc<data::Game>,
+    world: &WorldData<TL::Texture>,
+    player: PlayerKind,
+    center: glm::IVec2,
+) -> Result<()>
+where
+    TL: TextureLoader<'t>,
+    TL::Texture: Texture,
+    FL: FontLoader<'f>,
+    FL::Font: Font<Texture = TL::Texture>,
+    R: Canvas<'t, Texture = TL::Texture>,
+    E: input::EventPump,
+{
+    // a playtest is not part of the campaign and never gets to the finish screen
+    let campaign = data::Campaign { levels: vec![] };
+    let bindings = controls::get();
+    let mut game_play = screen::GamePlayData::load(texture_manager, Rc::new(campaign), game)?
+        .playtest(texture_manager, font_manager, world, player, center)?;
+
+    let update_duration = Duration::new(0, 1_000_000_000 / GAME_SPEED);
+    let mut timer = Timer::new();
+    let mut delta = Duration::default();
+    let color = ColorRGBA(60, 0, 70, 255);
+    loop {
+        let game_time = timer.update();
+        delta += game_time.since_update;
+        let mut loops: u32 = 0;
+        while delta >= update_duration && loops < MAX_SKIP {
+            let state = input_manager.update();
+            if state.game_quit() || state.did_press_key(Keycode::Escape) {
+                return Ok(());
+            }
+            let actions = Snapshot::capture(state, &bindings);
+            if game_play.update(update_duration, &actions).is_some() {
+                return Ok(());
+            }
+
+            delta -= update_duration;
+            loops += 1;
+        }
+        renderer.set_draw_color(color);
+        renderer.clear();
+        game_play.interpolate(interpolation(delta, update_duration));
+        game_play.before_draw()?;
+        renderer.show(&game_play)?;
+        renderer.present();
+    }
+}
@@ -56,6 +56,7 @@ impl<T> Obstacle<T> {
         texture_manager: &mut TextureManager<'t, TL>,
         ground: &data::Ground,
         obstacle: &data::Obstacle,
+        bottom: i32,
     ) -> Result<Self>
     where
         TL: TextureLoader<'t, Texture = T>,
@@ -65,7 +66,7 @@ impl<T> Obstacle<T> {
         let mut bl: glm::IVec2 = obstacle.bottom_left.into();
         bl.y += obstacle.count.y as i32;
         bl = bl * glm::to_ivec2(dims);
-        let tl = glm::ivec2(bl.x, 720 - bl.y);
+        let tl = glm::ivec2(bl.x, bottom - bl.y);
         let textures = Textures {
             center: ground.center.load(texture_manager)?,
             left: ground.left.load(texture_manager)?,
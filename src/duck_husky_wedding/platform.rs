@@ -0,0 +1,217 @@
+use data;
+use duck_husky_wedding::body::Body;
+use utils::Try;
+use errors::*;
+
+use glm;
+use moho;
+use moho::shape::Rectangle;
+use moho::renderer::{options, Renderer, Scene, TextureLoader, TextureManager};
+
+use std::rc::Rc;
+use std::time::Duration;
+
+struct Textures<T> {
+    left: Rc<T>,
+    center: Rc<T>,
+    right: Rc<T>,
+}
+
+impl<T> Clone for Textures<T> {
+    fn clone(&self) -> Self {
+        Textures {
+            left: Rc::clone(&self.left),
+            center: Rc::clone(&self.center),
+            right: Rc::clone(&self.right),
+        }
+    }
+}
+
+pub struct Platform<T> {
+    count: u32,
+    dims: glm::UVec2,
+    path: Vec<glm::DVec2>,
+    motion: data::Motion,
+    speed: f64,
+    target: usize,
+    forward: bool,
+    textures: Textures<T>,
+    pub one_way: bool,
+    pub tl: glm::DVec2,
+    // how far it moved during the last update
+    pub delta: glm::DVec2,
+    // where it was before this update, to draw it in between the two
+    previous: glm::DVec2,
+    blend: f64,
+}
+
+impl<T> Clone for Platform<T> {
+    fn clone(&self) -> Self {
+        Platform {
+            count: self.count,
+            dims: self.dims,
+            path: self.path.clone(),
+            motion: self.motion,
+            speed: self.speed,
+            target: self.target,
+            forward: self.forward,
+            textures: self.textures.clone(),
+            one_way: self.one_way,
+            tl: self.tl,
+            delta: self.delta,
+            previous: self.previous,
+            blend: self.blend,
+        }
+    }
+}
+
+impl<T> Platform<T> {
+    pub fn load<'t, TL>(
+        texture_manager: &mut TextureManager<'t, TL>,
+        ground: &data::Ground,
+        platform: &data::Platform,
+        bottom: i32,
+    ) -> Result<Self>
+    where
+        TL: TextureLoader<'t, Texture = T>,
+    {
+        let dims: glm::UVec2 = ground.out_size.into();
+        let path: Vec<_> = platform
+            .path
+            .iter()
+            .map(|p| {
+                let bl: glm::IVec2 = (*p).into();
+                let tl = glm::ivec2(bl.x * dims.x as i32, bottom - (bl.y + 1) * dims.y as i32);
+                glm::to_dvec2(tl)
+            })
+            .collect();
+        let tl = match path.first() {
+            Some(p) => *p,
+            None => bail!("platform has no points in its path"),
+        };
+        let textures = if platform.count == 1 {
+            let top = ground.top.load(texture_manager)?;
+            Textures {
+                left: Rc::clone(&top),
+                center: Rc::clone(&top),
+                right: top,
+            }
+        } else {
+            Textures {
+                left: ground.top_left.load(texture_manager)?,
+                center: ground.top.load(texture_manager)?,
+                right: ground.top_right.load(texture_manager)?,
+            }
+        };
+        Ok(Platform {
+            count: platform.count,
+            dims,
+            path,
+            motion: platform.motion,
+            speed: platform.speed * f64::from(dims.x),
+            target: 1,
+            forward: true,
+            textures,
+            one_way: platform.one_way,
+            tl,
+            delta: glm::dvec2(0., 0.),
+            previous: tl,
+            blend: 1.,
+        })
+    }
+
+    pub fn update(&mut self, duration: Duration) {
+        self.delta = glm::dvec2(0., 0.);
+        if self.path.len() < 2 {
+            return;
+        }
+
+        let secs = duration.as_secs() as f64 + f64::from(duration.subsec_nanos()) / 1e9;
+        let mut step = self.speed * secs;
+        // a fast platform may pass more than one point in a single update
+        for _ in 0..self.path.len() {
+            let to_target = self.path[self.target] - self.tl;
+            let distance = glm::length(to_target);
+            if distance > step {
+                let moved = to_target * (step / distance);
+                self.tl = self.tl + moved;
+                self.delta = self.delta + moved;
+                return;
+            }
+            self.tl = self.path[self.target];
+            self.delta = self.delta + to_target;
+            step -= distance;
+            self.advance();
+        }
+    }
+
+    pub fn remember(&mut self) {
+        self.previous = self.tl;
+    }
+
+    // takes on the look and path of `fresh` while carrying on from where it is
+    pub fn refresh(&mut self, fresh: &Platform<T>) {
+        self.textures = fresh.textures.clone();
+        self.dims = fresh.dims;
+        self.path = fresh.path.clone();
+    }
+
+    pub fn interpolate(&mut self, alpha: f64) {
+        self.blend = alpha;
+    }
+
+    pub fn top(&self) -> f64 {
+        self.tl.y
+    }
+
+    pub fn mtv(&self, object: &Body) -> Option<glm::DVec2> {
+        let platform = Rectangle {
+            top_left: self.tl,
+            dims: glm::dvec2(f64::from(self.dims.x * self.count), f64::from(self.dims.y)),
+        };
+        object.mtv(&platform)
+    }
+
+    fn advance(&mut self) {
+        let last = self.path.len() - 1;
+        match self.motion {
+            data::Motion::Linear => self.target = (self.target + 1) % self.path.len(),
+            data::Motion::PingPong => {
+                if self.forward && self.target == last {
+                    self.forward = false;
+                } else if !self.forward && self.target == 0 {
+                    self.forward = true;
+                }
+                if self.forward {
+                    self.target += 1;
+                } else {
+                    self.target -= 1;
+                }
+            }
+        }
+    }
+}
+
+impl<'t, R: Renderer<'t>> Scene<R> for Platform<R::Texture> {
+    fn show(&self, renderer: &mut R) -> moho::errors::Result<()> {
+        let tl = glm::to_ivec2(self.previous + (self.tl - self.previous) * self.blend);
+        (0..self.count)
+            .map(|i| {
+                let texture = if i == 0 {
+                    &self.textures.left
+                } else if i == self.count - 1 {
+                    &self.textures.right
+                } else {
+                    &self.textures.center
+                };
+                let dst = glm::ivec4(
+                    tl.x + (self.dims.x * i) as i32,
+                    tl.y,
+                    self.dims.x as i32,
+                    self.dims.y as i32,
+                );
+                renderer.copy(texture, options::at(dst))
+            })
+            .try()
+    }
+}
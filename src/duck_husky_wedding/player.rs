@@ -1,31 +1,88 @@
 use duck_husky_wedding::body::Body;
+use duck_husky_wedding::controls::{self, Snapshot};
 use duck_husky_wedding::flicker::Flicker;
+use duck_husky_wedding::ghost;
 use data;
 use errors::*;
 
 use glm;
 use moho::animation::{self, Animation};
-use moho::{self, input};
+use moho;
 use moho::renderer::{options, Renderer, Scene, Texture, TextureLoader, TextureManager};
-use sdl2::keyboard::Keycode;
 
-use std::ops::AddAssign;
 use std::time::Duration;
 use std::rc::Rc;
 
+// in pixels per second, and per second squared for gravity
+const RUN_SPEED: f64 = 270.;
+const JUMP_SPEED: f64 = 234.;
+const GRAVITY: f64 = 2700.;
+const MAX_FALL: f64 = 1500.;
+// jumps were tuned at this many updates a second and still count time held in them
+const TUNED_RATE: f64 = 60.;
+// how long holding jump keeps lifting the player, in those updates
+const JUMP_BOOST: f64 = 10.;
+// how far above the bottom of a level the floor is everywhere but over pits
+pub const FLOOR: f64 = 17.;
+
+// how much upward speed holding jump has added after `held` tuned updates; each one adds
+// less than the one before
+fn lift(held: f64) -> f64 {
+    let held = held.min(JUMP_BOOST);
+    let whole = held.floor();
+    let steps: f64 = (1..whole as u32 + 1).map(|k| 1. / f64::from(k)).sum();
+    JUMP_SPEED * (steps + (held - whole) / (whole + 1.))
+}
+
+// where a player running right is after each tuned update of a jump held for as long
+// as it helps, relative to the take off point; ends once it has fallen `depth` below it
+pub fn jump_arc(depth: f64) -> Vec<glm::DVec2> {
+    let step = 1. / TUNED_RATE;
+    let mut arc = vec![];
+    let mut position = glm::dvec2(0., 0.);
+    let mut speed = -JUMP_SPEED;
+    let mut held = 0.;
+    while position.y < depth {
+        speed = (speed + GRAVITY * step).min(MAX_FALL);
+        position = position + glm::dvec2(RUN_SPEED, speed) * step;
+        arc.push(position);
+        if held < JUMP_BOOST {
+            let before = lift(held);
+            held += 1.;
+            speed -= lift(held) - before;
+        }
+    }
+    arc
+}
+
 enum Action<T> {
     Moving(Animation<T>),
-    Jumping(Rc<T>, u32),
+    Jumping(Rc<T>, f64),
     Standing(Rc<T>),
 }
 
 pub struct Player<T> {
+    // in pixels per second
     pub delta_pos: glm::DVec2,
+    // how far it means to move during this update, before anything pushes back
+    pub step: glm::DVec2,
     pub dst_rect: glm::DVec4,
+    // where it was before this update, to draw it in between the two
+    previous: glm::DVec4,
+    blend: f64,
     pub invincibility: Option<Invincibility>,
+    // lets it jump once more while in the air
+    pub double_jump: bool,
+    // how many times faster than usual it runs
+    pub boost: f64,
+    // whether it left the ground or jumped again in the air during this update
+    pub jumped: bool,
+    air_jumped: bool,
     body: Vec<data::Shape>,
     legs: Vec<data::Shape>,
     action: Action<T>,
+    // how long the running animation has played, for the ghost to show the same tile
+    stride: Duration,
     animation: animation::Data<T>,
     texture: Rc<T>,
     backwards: bool,
@@ -38,9 +95,8 @@ pub struct Invincibility {
 }
 
 impl Invincibility {
-    fn new() -> Self {
+    fn new(duration: Duration) -> Self {
         let flicker = Flicker::new(Duration::from_millis(100));
-        let duration = Duration::from_secs(1);
         Invincibility { flicker, duration }
     }
 
@@ -89,8 +145,16 @@ impl<T> Player<T> {
         Player {
             action: Action::Standing(Rc::clone(&texture)),
             delta_pos: glm::dvec2(0., 0.),
+            step: glm::dvec2(0., 0.),
+            previous: dst_rect,
+            blend: 1.,
             backwards: false,
             invincibility: None,
+            double_jump: false,
+            boost: 1.,
+            jumped: false,
+            air_jumped: false,
+            stride: Duration::default(),
             animation,
             texture,
             dst_rect,
@@ -99,6 +163,38 @@ impl<T> Player<T> {
         }
     }
 
+    // takes on `data` without moving its feet or losing its momentum
+    pub fn reload<'t, TL>(
+        &mut self,
+        data: &data::Player,
+        texture_manager: &mut TextureManager<'t, TL>,
+    ) -> Result<()>
+    where
+        T: Texture,
+        TL: TextureLoader<'t, Texture = T>,
+    {
+        let animation = data.animation.load(texture_manager)?;
+        let texture = data.idle_texture.load(texture_manager)?;
+        let dims = glm::DVec2::from(data.out_size);
+        self.dst_rect.y += self.dst_rect.w - dims.y;
+        self.dst_rect.z = dims.x;
+        self.dst_rect.w = dims.y;
+        self.previous = self.dst_rect;
+        self.action = match self.action {
+            Action::Moving(_) => {
+                self.stride = Duration::default();
+                Action::Moving(animation.clone().start())
+            }
+            Action::Jumping(_, held) => Action::Jumping(Rc::clone(&texture), held),
+            Action::Standing(_) => Action::Standing(Rc::clone(&texture)),
+        };
+        self.animation = animation;
+        self.texture = texture;
+        self.body = data.body.clone();
+        self.legs = data.legs.clone();
+        Ok(())
+    }
+
     pub fn body(&self) -> Body {
         Body::new(&self.dst_rect, &self.body, self.backwards)
     }
@@ -107,34 +203,51 @@ impl<T> Player<T> {
         Body::new(&self.dst_rect, &self.legs, self.backwards)
     }
 
-    pub fn process(&mut self, input: &input::State) {
-        let left = input.is_key_down(Keycode::Left);
-        let right = input.is_key_down(Keycode::Right);
-        let up = input.is_key_down(Keycode::Up);
+    pub fn is_backwards(&self) -> bool {
+        self.backwards
+    }
+
+    pub fn process(&mut self, input: &Snapshot, delta: Duration) {
+        let secs = delta.as_secs() as f64 + f64::from(delta.subsec_nanos()) / 1e9;
+        let horizontal = input.horizontal();
+        let up = input.is_down(controls::Action::Jump);
+
+        let pressed = input.did_press(controls::Action::Jump);
+        self.jumped = false;
 
         if up {
             match self.action {
-                Action::Jumping(_, ref mut held) => if *held < 10 {
-                    held.add_assign(1);
-                    self.delta_pos.y -= 3.9 / f64::from(*held)
+                Action::Jumping(_, ref mut held) => if pressed && self.double_jump
+                    && !self.air_jumped
+                {
+                    self.air_jumped = true;
+                    self.jumped = true;
+                    *held = 0.;
+                    self.delta_pos.y = -JUMP_SPEED;
+                } else if *held < JUMP_BOOST {
+                    let before = lift(*held);
+                    *held += secs * TUNED_RATE;
+                    self.delta_pos.y -= lift(*held) - before;
                 },
                 _ => {
-                    self.delta_pos.y = -3.9;
+                    self.jumped = true;
+                    self.delta_pos.y = -JUMP_SPEED;
                 }
             }
         } else if let Action::Jumping(_, ref mut held) = self.action {
-            held.add_assign(15);
+            *held = JUMP_BOOST;
         }
 
-        if left ^ right {
-            self.backwards = left;
-            self.delta_pos.x = if left { -4.5 } else { 4.5 };
+        if horizontal != 0. {
+            self.backwards = horizontal < 0.;
+            self.delta_pos.x = RUN_SPEED * self.boost * horizontal;
         } else {
             self.delta_pos.x = 0.;
         }
 
-        self.delta_pos.y += 0.75;
-        self.delta_pos.y = self.delta_pos.y.min(25.);
+        self.delta_pos.y += GRAVITY * secs;
+        self.delta_pos.y = self.delta_pos.y.min(MAX_FALL);
+        self.step = self.delta_pos * secs;
     }
 
     pub fn update(&mut self, (force, on_floor): (glm::DVec2, bool), delta: Duration) {
@@ -142,19 +255,23 @@ impl<T> Player<T> {
             self.invincibility = i.update(delta);
         }
 
+        if on_floor {
+            self.air_jumped = false;
+        }
         let same_y = self.delta_pos.y.signum() == force.y.signum();
 
         let next_action = match self.action {
             Action::Moving(ref mut a) => if !on_floor {
-                Some(Action::Jumping(Rc::clone(&self.texture), 0))
+                Some(Action::Jumping(Rc::clone(&self.texture), 0.))
             } else if self.delta_pos.x == 0. {
                 Some(Action::Standing(Rc::clone(&self.texture)))
             } else {
                 a.animate(delta);
+                self.stride += delta;
                 None
             },
             Action::Standing(_) => if !on_floor {
-                Some(Action::Jumping(Rc::clone(&self.texture), 0))
+                Some(Action::Jumping(Rc::clone(&self.texture), 0.))
             } else if self.delta_pos.x == 0. {
                 None
             } else {
@@ -163,8 +280,9 @@ impl<T> Player<T> {
             },
             Action::Jumping(_, ref mut held) => if !on_floor || (on_floor && self.delta_pos.y < 0.)
             {
+                // bumping into something overhead ends the boost
                 if self.delta_pos.y.abs() > 0. && force.y.abs() > 0. && !same_y {
-                    held.add_assign(100);
+                    *held = JUMP_BOOST;
                 }
                 None
             } else if self.delta_pos.x == 0. {
@@ -176,22 +294,75 @@ impl<T> Player<T> {
         };
 
         if let Some(a) = next_action {
+            self.stride = Duration::default();
             self.action = a;
         }
 
-        self.dst_rect.x += self.delta_pos.x + force.x;
-        self.dst_rect.y += self.delta_pos.y + force.y;
+        self.dst_rect.x += self.step.x + force.x;
+        self.dst_rect.y += self.step.y + force.y;
 
         if self.delta_pos.y.abs() > 0. && force.y.abs() > 0. && !same_y {
             self.delta_pos.y = 0.
         }
 
-        let max_y = 720. - 17. - self.dst_rect.w;
-        self.dst_rect.y = self.dst_rect.y.min(max_y);
+    }
+
+    // the lowest the player can go anywhere but over a pit, in a level ending at `bottom`
+    pub fn floor(&self, bottom: f64) -> f64 {
+        bottom - FLOOR - self.dst_rect.w
+    }
+
+    pub fn clamp_to_floor(&mut self, bottom: f64) {
+        self.dst_rect.y = self.dst_rect.y.min(self.floor(bottom));
+    }
+
+    pub fn respawn(&mut self, top_left: glm::DVec2) {
+        self.dst_rect.x = top_left.x;
+        self.dst_rect.y = top_left.y;
+        self.previous = self.dst_rect;
+        self.delta_pos = glm::dvec2(0., 0.);
+        self.backwards = false;
+        self.action = Action::Standing(Rc::clone(&self.texture));
+        self.invincible();
+    }
+
+    // called before every update so drawing can blend from where it is now
+    pub fn remember(&mut self) {
+        self.previous = self.dst_rect;
+    }
+
+    // 0 draws it where it was before the last update and 1 where it is now
+    pub fn interpolate(&mut self, alpha: f64) {
+        self.blend = alpha;
+    }
+
+    // thrown back up off an enemy it landed on
+    pub fn bounce(&mut self) {
+        self.delta_pos.y = -2. * JUMP_SPEED;
+        self.action = Action::Jumping(Rc::clone(&self.texture), JUMP_BOOST);
     }
 
     pub fn invincible(&mut self) {
-        self.invincibility = Some(Invincibility::new());
+        self.invincible_for(Duration::from_secs(1));
+    }
+
+    pub fn invincible_for(&mut self, duration: Duration) {
+        self.invincibility = Some(Invincibility::new(duration));
+    }
+
+    pub fn ghost_frame(&self) -> ghost::Frame {
+        let (pose, stride) = match self.action {
+            Action::Moving(_) => (ghost::Pose::Moving, Some(self.stride)),
+            Action::Jumping(..) => (ghost::Pose::Jumping, None),
+            Action::Standing(_) => (ghost::Pose::Standing, None),
+        };
+        let dst = self.dst_rect;
+        ghost::Frame {
+            pose,
+            stride,
+            dst: [dst.x, dst.y, dst.z, dst.w],
+            backwards: self.backwards,
+        }
     }
 }
 
@@ -201,8 +372,8 @@ impl<'t, R: Renderer<'t>> Scene<R> for Player<R::Texture> {
             .map(|i| i.flicker.is_shown())
             .unwrap_or(true)
         {
-            let dst = glm::to_ivec4(self.dst_rect);
-            let mut options = options::at(dst);
+            let dst = self.previous + (self.dst_rect - self.previous) * self.blend;
+            let mut options = options::at(glm::to_ivec4(dst));
             if self.backwards {
                 options = options.flip(options::Flip::Horizontal);
             }
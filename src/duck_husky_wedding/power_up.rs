@@ -0,0 +1,118 @@
+use data;
+use errors::*;
+use utils::Try;
+
+use glm;
+use moho;
+use moho::renderer::{align, options, ColorRGBA, Renderer, Scene, TextureLoader, TextureManager};
+use sdl2::rect::Rect;
+
+use std::rc::Rc;
+use std::time::Duration;
+
+const ICON_SIZE: u32 = 32;
+const ICON_TOP: i32 = 40;
+const ICONS_LEFT: i32 = 20;
+
+struct Kind<T> {
+    kind: data::PowerUpKind,
+    icon: Rc<T>,
+    duration: Duration,
+}
+
+struct Effect<T> {
+    kind: data::PowerUpKind,
+    icon: Rc<T>,
+    total: Duration,
+    remaining: Duration,
+}
+
+fn millis(d: Duration) -> u64 {
+    d.as_secs() * 1000 + u64::from(d.subsec_nanos()) / 1_000_000
+}
+
+// the power ups the player picked up that are still going
+pub struct Effects<T> {
+    kinds: Vec<Kind<T>>,
+    active: Vec<Effect<T>>,
+}
+
+impl<T> Effects<T> {
+    pub fn load<'t, TL>(
+        texture_manager: &mut TextureManager<'t, TL>,
+        power_ups: &[data::PowerUp],
+    ) -> Result<Self>
+    where
+        TL: TextureLoader<'t, Texture = T>,
+    {
+        let kinds = power_ups
+            .iter()
+            .map(|p| -> Result<Kind<T>> {
+                Ok(Kind {
+                    kind: p.kind,
+                    icon: p.icon.load(texture_manager)?,
+                    duration: Duration::from_millis(p.duration),
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Effects {
+            kinds,
+            active: vec![],
+        })
+    }
+
+    // starts `kind` over if it was already going; returns how long it lasts
+    pub fn start(&mut self, kind: data::PowerUpKind) -> Duration {
+        let (icon, duration) = match self.kinds.iter().find(|k| k.kind == kind) {
+            Some(k) => (Rc::clone(&k.icon), k.duration),
+            None => return Duration::default(),
+        };
+        // extra time is over as soon as it is picked up
+        if kind != data::PowerUpKind::ExtraTime {
+            self.active.retain(|e| e.kind != kind);
+            self.active.push(Effect {
+                kind,
+                icon,
+                total: duration,
+                remaining: duration,
+            });
+        }
+        duration
+    }
+
+    pub fn update(&mut self, delta: Duration) {
+        for e in &mut self.active {
+            e.remaining = e.remaining.checked_sub(delta).unwrap_or_default();
+        }
+        self.active.retain(|e| e.remaining > Duration::default());
+    }
+
+    pub fn is_active(&self, kind: data::PowerUpKind) -> bool {
+        self.active.iter().any(|e| e.kind == kind)
+    }
+
+    pub fn clear(&mut self) {
+        self.active.clear();
+    }
+}
+
+impl<'t, R: Renderer<'t>> Scene<R> for Effects<R::Texture> {
+    fn show(&self, renderer: &mut R) -> moho::errors::Result<()> {
+        self.active
+            .iter()
+            .enumerate()
+            .map(|(i, e)| {
+                let left = ICONS_LEFT + (ICON_SIZE as i32 + 8) * i as i32;
+                let dst = align::top(ICON_TOP)
+                    .left(left)
+                    .dims(glm::uvec2(ICON_SIZE, ICON_SIZE));
+                renderer.copy(&*e.icon, options::at(dst))?;
+                // shrinks as it runs out
+                let width = ICON_SIZE as u64 * millis(e.remaining) / millis(e.total).max(1);
+                renderer.set_draw_color(ColorRGBA(255, 255, 0, 255));
+                let bar = Rect::new(left, ICON_TOP + ICON_SIZE as i32 + 2, width as u32, 4);
+                renderer.fill_rects(&[bar])
+            })
+            .try()
+    }
+}
@@ -0,0 +1,79 @@
+use data::{self, Campaign, Unlock};
+use errors::*;
+
+use serde_yaml;
+
+use std::cmp;
+use std::collections::BTreeMap;
+
+const FILE: &'static str = "progress.yaml";
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct LevelProgress {
+    #[serde(default)] pub unlocked: bool,
+    #[serde(default)] pub completed: bool,
+    #[serde(default)] pub best_score: Option<u32>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct Progress {
+    #[serde(default)] levels: BTreeMap<String, LevelProgress>,
+}
+
+impl Progress {
+    pub fn level(&self, campaign: &Campaign, index: usize) -> LevelProgress {
+        self.levels
+            .get(&campaign.levels[index].file)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn total_score(&self) -> u32 {
+        self.levels.values().filter_map(|l| l.best_score).sum()
+    }
+
+    pub fn is_unlocked(&self, campaign: &Campaign, index: usize) -> bool {
+        if self.level(campaign, index).unlocked {
+            return true;
+        }
+
+        match campaign.levels[index].unlock {
+            Unlock::Always => true,
+            Unlock::Previous => index == 0 || self.level(campaign, index - 1).completed,
+            Unlock::TotalScore(s) => self.total_score() >= s,
+        }
+    }
+
+    pub fn complete(&mut self, campaign: &Campaign, index: usize, score: u32) {
+        {
+            let level = self.levels
+                .entry(campaign.levels[index].file.clone())
+                .or_insert_with(Default::default);
+            level.unlocked = true;
+            level.completed = true;
+            level.best_score = Some(level.best_score.map_or(score, |b| cmp::max(b, score)));
+        }
+
+        let unlocked: Vec<_> = (0..campaign.levels.len())
+            .filter(|&i| self.is_unlocked(campaign, i))
+            .collect();
+        for i in unlocked {
+            self.levels
+                .entry(campaign.levels[i].file.clone())
+                .or_insert_with(Default::default)
+                .unlocked = true;
+        }
+    }
+}
+
+pub fn get() -> Progress {
+    data::open_user(FILE)
+        .chain_err(|| "")
+        .and_then(|f| serde_yaml::from_reader(f).map_err(Into::into))
+        .unwrap_or_default()
+}
+
+pub fn create(progress: &Progress) -> Result<()> {
+    let file = data::create_user(FILE)?;
+    serde_yaml::to_writer(file, progress).map_err(Into::into)
+}
@@ -0,0 +1,165 @@
+use data;
+use duck_husky_wedding::font;
+use utils::Try;
+use errors::*;
+
+use moho;
+use moho::renderer::{align, options, Canvas, ColorRGBA, Font, Scene, Texture};
+use sdl2::rect::Rect;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+// looking at the disk every update would be wasteful
+const POLL_MILLIS: u64 = 500;
+const MARGIN: i32 = 10;
+
+fn stamp(path: PathBuf) -> Option<(PathBuf, SystemTime)> {
+    let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+    Some((path, modified))
+}
+
+// notices files changing on disk by polling when they were last modified
+pub struct Watcher {
+    // for a directory it is the files right inside it that are watched
+    paths: Vec<PathBuf>,
+    stamps: Vec<(PathBuf, SystemTime)>,
+    waited: Duration,
+}
+
+impl Watcher {
+    pub fn new(paths: Vec<String>) -> Self {
+        let paths = paths.into_iter().map(PathBuf::from).collect();
+        let mut watcher = Watcher {
+            paths,
+            stamps: vec![],
+            waited: Duration::default(),
+        };
+        watcher.forget();
+        watcher
+    }
+
+    fn scan(&self) -> Vec<(PathBuf, SystemTime)> {
+        let mut stamps = vec![];
+        for p in &self.paths {
+            match fs::read_dir(p) {
+                Ok(entries) => stamps.extend(
+                    entries
+                        .filter_map(|e| e.ok())
+                        .map(|e| e.path())
+                        .filter_map(stamp),
+                ),
+                Err(_) => stamps.extend(stamp(p.clone())),
+            }
+        }
+        stamps.sort();
+        stamps
+    }
+
+    // takes the files as they are now, so changes made by the game itself go unreported
+    pub fn forget(&mut self) {
+        self.stamps = self.scan();
+    }
+
+    // the files changed, added or removed since the last time it looked
+    pub fn poll(&mut self, delta: Duration) -> Vec<PathBuf> {
+        self.waited += delta;
+        if self.waited < Duration::from_millis(POLL_MILLIS) {
+            return vec![];
+        }
+        self.waited = Duration::default();
+        let stamps = self.scan();
+        let mut changed: Vec<_> = {
+            let (old, new) = (&self.stamps, &stamps);
+            new.iter()
+                .filter(|s| !old.contains(s))
+                .chain(old.iter().filter(|s| !new.contains(s)))
+                .map(|&(ref p, _)| p.clone())
+                .collect()
+        };
+        changed.sort();
+        changed.dedup();
+        self.stamps = stamps;
+        changed
+    }
+}
+
+// what the changed files are to the game
+pub struct Changes {
+    pub game: bool,
+    pub sprites: bool,
+    pub levels: Vec<PathBuf>,
+}
+
+impl Changes {
+    // anything that is not the game data or a sprite is taken to be a level
+    pub fn sort(changed: Vec<PathBuf>) -> Self {
+        let game = PathBuf::from(data::media("game_data.yaml"));
+        let sprites = PathBuf::from(data::media("sprites"));
+        let (assets, levels): (Vec<_>, Vec<_>) = changed
+            .into_iter()
+            .partition(|p| *p == game || p.starts_with(&sprites));
+        Changes {
+            game: assets.contains(&game),
+            sprites: assets.iter().any(|p| p.starts_with(&sprites)),
+            levels,
+        }
+    }
+
+    pub fn has_level(&self, level: &str) -> bool {
+        self.levels.iter().any(|l| l == Path::new(level))
+    }
+}
+
+// what went wrong with the last reload, drawn over whatever is on screen
+pub struct Report<T> {
+    lines: Vec<T>,
+}
+
+impl<T> Default for Report<T> {
+    fn default() -> Self {
+        Report { lines: vec![] }
+    }
+}
+
+impl<T> Report<T> {
+    // one line for the error and one for each of its causes
+    pub fn load<FM>(font_manager: &mut FM, error: &Error) -> Result<Self>
+    where
+        FM: font::Manager,
+        FM::Font: Font<Texture = T>,
+    {
+        let color = ColorRGBA(255, 255, 255, 255);
+        let font = font_manager.load(font::Kind::KenPixel, 24)?;
+        let lines = error
+            .iter()
+            .map(|e| font.texturize(&e.to_string(), &color).map_err(Into::into))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Report { lines })
+    }
+}
+
+impl<'t, R: Canvas<'t>> Scene<R> for Report<R::Texture>
+where
+    R::Texture: Texture,
+{
+    fn show(&self, renderer: &mut R) -> moho::errors::Result<()> {
+        if self.lines.is_empty() {
+            return Ok(());
+        }
+        let height: i32 = self.lines.iter().map(|l| l.dims().y as i32).sum();
+        renderer.set_draw_color(ColorRGBA(120, 0, 0, 255));
+        renderer.fill_rects(&[Rect::new(0, 0, 1280, (height + 2 * MARGIN) as u32)])?;
+
+        let mut top = align::top(MARGIN);
+        self.lines
+            .iter()
+            .map(|l| {
+                let options = options::at(top.left(MARGIN));
+                top = top.nudge(l.dims().y as i32);
+                renderer.copy(l, options)
+            })
+            .try()
+    }
+}
@@ -0,0 +1,166 @@
+use data;
+use duck_husky_wedding::controls::{Action, Snapshot};
+use duck_husky_wedding::screen::PlayerKind;
+use errors::*;
+
+use serde_yaml;
+
+use std::fs::{self, File};
+use std::path::Path;
+
+pub fn last_run() -> String {
+    data::user("replays/last.replay")
+}
+
+// consecutive ticks that share the same snapshot
+#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+struct Run(u32, Snapshot);
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Recording {
+    pub level: String,
+    pub player: PlayerKind,
+    runs: Vec<Run>,
+}
+
+impl Recording {
+    pub fn new(level: String, player: PlayerKind) -> Self {
+        Recording {
+            level,
+            player,
+            runs: vec![],
+        }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let f = File::open(path)?;
+        serde_yaml::from_reader(&f).map_err(Into::into)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = File::create(path)?;
+        serde_yaml::to_writer(file, self).map_err(Into::into)
+    }
+
+    pub fn push(&mut self, snapshot: Snapshot) {
+        if let Some(&mut Run(ref mut count, s)) = self.runs.last_mut() {
+            if s == snapshot {
+                *count += 1;
+                return;
+            }
+        }
+        self.runs.push(Run(1, snapshot));
+    }
+
+    pub fn playback(self) -> Playback {
+        Playback {
+            recording: self,
+            run: 0,
+            tick: 0,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Step {
+    ticks: u32,
+    #[serde(default)] actions: Vec<Action>,
+}
+
+// hand written input: each step holds `actions` down for `ticks` updates
+#[derive(Debug, Deserialize)]
+pub struct Script {
+    pub level: String,
+    pub player: PlayerKind,
+    steps: Vec<Step>,
+}
+
+impl Script {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let f = File::open(path)?;
+        serde_yaml::from_reader(&f).map_err(Into::into)
+    }
+
+    pub fn into_recording(self) -> Recording {
+        let mut recording = Recording::new(self.level, self.player);
+        let mut held: Vec<Action> = vec![];
+        for step in self.steps {
+            let actions = step.actions;
+            let pressed: Vec<_> = actions
+                .iter()
+                .cloned()
+                .filter(|a| !held.contains(a))
+                .collect();
+            for tick in 0..step.ticks {
+                let pressed = if tick == 0 { pressed.as_slice() } else { &[] };
+                recording.push(Snapshot::from_actions(&actions, pressed));
+            }
+            held = actions;
+        }
+        recording
+    }
+}
+
+pub struct Playback {
+    recording: Recording,
+    run: usize,
+    tick: u32,
+}
+
+impl Playback {
+    pub fn is_done(&self) -> bool {
+        self.run >= self.recording.runs.len()
+    }
+
+    pub fn next(&mut self) -> Snapshot {
+        match self.recording.runs.get(self.run).cloned() {
+            None => Snapshot::default(),
+            Some(Run(count, snapshot)) => {
+                self.tick += 1;
+                if self.tick >= count {
+                    self.tick = 0;
+                    self.run += 1;
+                }
+                snapshot
+            }
+        }
+    }
+}
+
+pub enum Source {
+    Record(Recording),
+    Replay(Playback),
+    // played live but never saved, e.g. when playtesting from the level editor
+    Live,
+}
+
+impl Source {
+    pub fn next(&mut self, actions: Snapshot) -> Snapshot {
+        match *self {
+            Source::Record(ref mut r) => {
+                r.push(actions);
+                actions
+            }
+            Source::Replay(ref mut p) => p.next(),
+            Source::Live => actions,
+        }
+    }
+
+    pub fn is_replay(&self) -> bool {
+        match *self {
+            Source::Record(_) | Source::Live => false,
+            Source::Replay(_) => true,
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        match *self {
+            Source::Record(_) => true,
+            Source::Replay(_) | Source::Live => false,
+        }
+    }
+}
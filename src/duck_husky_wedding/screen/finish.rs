@@ -1,14 +1,14 @@
+use duck_husky_wedding::controls::{Action, Snapshot};
 use duck_husky_wedding::edit_text::EditText;
-use duck_husky_wedding::high_score::{self, ScoreEntry};
+use duck_husky_wedding::high_score::{self, HighScores, ScoreEntry};
+use duck_husky_wedding::progress;
+use data;
 
 use errors::*;
 
 use glm;
 use moho;
 use moho::renderer::{align, options, Canvas, ColorRGBA, Font, Scene, Texture};
-use moho::input;
-use sdl2::rect::Rect;
-use sdl2::keyboard::Keycode;
 
 use std::rc::Rc;
 use std::time::Duration;
@@ -17,27 +17,33 @@ pub struct Data<F> {
     pub title_font: Rc<F>,
     pub detail_font: Rc<F>,
     pub view: glm::IVec4,
+    pub time_limit: Duration,
+    pub campaign: Rc<data::Campaign>,
+    pub attempt: super::Attempt,
+}
+
+pub fn time_bonus(remaining: Duration) -> u32 {
+    5 * remaining.as_secs() as u32
 }
 
 pub struct ScoreData<T, F> {
-    previous: Vec<ScoreEntry>,
+    previous: HighScores,
+    level: String,
+    player: super::PlayerKind,
     current: u32,
+    time: Duration,
     name: EditText<T, F>,
 }
 
 impl<T, F: Font<Texture = T>> ScoreData<T, F> {
-    fn extract(&self) -> Option<Vec<ScoreEntry>> {
+    fn extract(&self) -> Option<HighScores> {
         let name = self.name.extract();
         if name.is_empty() {
             None
         } else {
             let mut updated = self.previous.clone();
-            updated.push(ScoreEntry {
-                name,
-                score: self.current,
-            });
-            updated.sort_by(|a, b| a.score.cmp(&b.score).reverse());
-            updated.truncate(10);
+            let entry = ScoreEntry::new(name, self.current, self.time);
+            updated.insert(&self.level, self.player, entry);
             Some(updated)
         }
     }
@@ -51,6 +57,9 @@ pub struct Finish<T, F> {
     time: T,
     total: T,
     score_entry: Option<ScoreData<T, F>>,
+    next: Option<super::Kind>,
+    level: usize,
+    player: super::PlayerKind,
 }
 
 impl<T, F: Font<Texture = T>> Finish<T, F> {
@@ -63,8 +72,11 @@ impl<T, F: Font<Texture = T>> Finish<T, F> {
         let instructions = data.title_font
             .texturize("<PRESS ENTER TO CONTINUE>", &yellow)?;
 
-        let duration = 5 * duration.as_secs();
-        let new_score = duration as u32 + score;
+        let elapsed = data.time_limit
+            .checked_sub(duration)
+            .unwrap_or_default();
+        let duration = time_bonus(duration);
+        let new_score = duration + score;
         let total = data.detail_font
             .texturize(&format!("     total: {:>06}", new_score), &white)?;
 
@@ -74,6 +86,17 @@ impl<T, F: Font<Texture = T>> Finish<T, F> {
         let time = data.detail_font
             .texturize(&format!("time bonus: {:>06}", duration), &white)?;
 
+        let super::Attempt { player, level, .. } = data.attempt;
+        let next = {
+            // the game play saved this when the run ended, but that may have failed
+            let mut progress = progress::get();
+            progress.complete(&data.campaign, level, new_score);
+            data.campaign
+                .next(level)
+                .filter(|&l| progress.is_unlocked(&data.campaign, l))
+                .map(|l| super::Kind::GamePlay(super::Attempt { level: l, ..data.attempt }))
+        };
+
         let name = EditText::load(
             "Enter Name: ",
             glm::ivec2(369, 400),
@@ -82,19 +105,18 @@ impl<T, F: Font<Texture = T>> Finish<T, F> {
 
         let score_entry = {
             let previous = high_score::get();
-            let min_score = if previous.len() < 10 {
-                None
-            } else {
-                previous.iter().map(|s| s.score).min()
-            };
-
-            match min_score {
-                Some(min_score) if min_score > new_score => None,
-                _ => Some(ScoreData {
+            let level = data.campaign.levels[level].file.clone();
+            if previous.qualifies(&level, player, new_score) {
+                Some(ScoreData {
                     previous,
+                    level,
                     name,
+                    player,
                     current: new_score,
-                }),
+                    time: elapsed,
+                })
+            } else {
+                None
             }
         };
 
@@ -106,6 +128,9 @@ impl<T, F: Font<Texture = T>> Finish<T, F> {
             time,
             total,
             score_entry,
+            next,
+            level,
+            player,
         })
     }
 
@@ -117,18 +142,20 @@ impl<T, F: Font<Texture = T>> Finish<T, F> {
         }
     }
 
-    pub fn update(&mut self, elapsed: Duration, state: &input::State) -> Option<super::Kind> {
-        if state.did_press_key(Keycode::Return) {
+    pub fn update(&mut self, elapsed: Duration, actions: &Snapshot) -> Option<super::Kind> {
+        if actions.did_press(Action::Confirm) {
+            let next = self.next;
+            let (level, player) = (self.level, self.player);
             match self.score_entry {
-                None => Some(super::Kind::Menu),
+                None => Some(next.unwrap_or(super::Kind::Menu)),
                 Some(ref s) => s.extract().map(|ref s| {
                     high_score::create(s).expect("could not write to high score file");
-                    super::Kind::HighScore
+                    next.unwrap_or(super::Kind::HighScore(level, player))
                 }),
             }
         } else {
             if let Some(ref mut s) = self.score_entry {
-                s.name.update(elapsed, state);
+                s.name.update(elapsed, actions);
             }
             None
         }
@@ -140,24 +167,7 @@ where
     R::Texture: Texture,
 {
     fn show(&self, renderer: &mut R) -> moho::errors::Result<()> {
-        renderer.set_draw_color(ColorRGBA(0, 0, 0, 255));
-        renderer.fill_rects(&[
-            Rect::new(
-                self.view.x,
-                self.view.y,
-                self.view.z as u32,
-                self.view.w as u32,
-            ),
-        ])?;
-        renderer.set_draw_color(ColorRGBA(60, 0, 70, 255));
-        renderer.fill_rects(&[
-            Rect::new(
-                self.view.x + 6,
-                self.view.y + 6,
-                self.view.z as u32 - 12,
-                self.view.w as u32 - 12,
-            ),
-        ])?;
+        super::show_frame(renderer, &self.view)?;
 
         let mut top = align::top(self.view.y);
         //title
@@ -1,8 +1,17 @@
 use duck_husky_wedding::player::Player;
+use duck_husky_wedding::audio::{Music, Sound};
+use duck_husky_wedding::collectable;
+use duck_husky_wedding::power_up::Effects;
 use duck_husky_wedding::world::{self, World};
-use duck_husky_wedding::camera::ViewPort;
+use duck_husky_wedding::camera::{Follow, ViewPort};
 use duck_husky_wedding::hud::TextBox;
 use duck_husky_wedding::font;
+use duck_husky_wedding::ghost::{self, Ghost};
+use duck_husky_wedding::progress;
+use duck_husky_wedding::controls::{Action, Snapshot};
+use duck_husky_wedding::replay::{self, Recording, Source};
+use duck_husky_wedding::reload::Changes;
+use super::pause::{Choice, Pause};
 use utils::{Center, Try};
 use data;
 use errors::*;
@@ -10,27 +19,48 @@ use errors::*;
 use utils::VecUtils;
 
 use glm;
-use moho::{self, input};
+use moho;
 use moho::renderer::{align, options, Canvas, ColorRGBA, Font, Renderer, Scene, Texture,
                      TextureLoader, TextureManager};
 use moho::shape::Shape;
 use sdl2::rect::Rect;
-use sdl2::keyboard::Keycode;
 
 use std::cmp;
+use std::fmt;
+use std::mem;
 use std::rc::Rc;
 use std::time::Duration;
 
+const LIVES: u32 = 3;
+const MAX_HEALTH: u32 = 3;
+const FADE_MILLIS: u64 = 600;
+const SPEED_BOOST: f64 = 1.5;
+// in pixels and pixels per second
+const MAGNET_RANGE: f64 = 250.;
+const MAGNET_SPEED: f64 = 600.;
+// in pixels per second
+const SPLASH_RISE: f64 = 120.;
+const SINK_SPEED: f64 = 240.;
+// how much bigger the heart gets each second
+const HEART_GROWTH: f64 = 3.;
+
+fn seconds(d: Duration) -> f64 {
+    d.as_secs() as f64 + f64::from(d.subsec_nanos()) / 1e9
+}
+
 struct Splash<T> {
     texture: T,
     duration: Duration,
     dst: options::Destination,
+    // how far it floated up from `dst`
+    risen: f64,
 }
 
 impl<'t, R: Renderer<'t>> Scene<R> for Splash<R::Texture> {
     fn show(&self, renderer: &mut R) -> moho::errors::Result<()> {
         if self.is_active() {
-            renderer.copy(&self.texture, options::at(self.dst))
+            let dst = self.dst.nudge(glm::ivec2(0, -self.risen as i32));
+            renderer.copy(&self.texture, options::at(dst))
         } else {
             Ok(())
         }
@@ -43,7 +73,7 @@ impl<T> Splash<T> {
     }
 
     fn update(&mut self, delta: Duration) {
-        self.dst = self.dst.nudge(glm::ivec2(0, -2));
+        self.risen += SPLASH_RISE * seconds(delta);
 
         self.duration = match self.duration.checked_sub(delta) {
             None => Duration::default(),
@@ -58,17 +88,47 @@ pub struct Heart<T> {
     texture: Rc<T>,
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
 pub enum PlayerKind {
     Duck,
     Husky,
 }
 
+#[derive(Clone, Copy)]
+pub struct Attempt {
+    pub player: PlayerKind,
+    pub level: usize,
+    pub ghost: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Outcome {
+    pub finished: bool,
+    pub score: u32,
+    pub remaining: Duration,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} with score {} and {}.{:03}s left",
+            if self.finished { "finished" } else { "failed" },
+            self.score,
+            self.remaining.as_secs(),
+            self.remaining.subsec_nanos() / 1_000_000
+        )
+    }
+}
+
 enum State<T, F> {
     Running,
     Transition,
     Finished(super::finish::Finish<T, F>),
-    TimeUp {
+    Paused(Pause<T>),
+    // counts down the fade to black and back while the player respawns
+    Respawning(Duration),
+    Lost {
         view: glm::IVec4,
         title: T,
         instructions: T,
@@ -77,44 +137,115 @@ enum State<T, F> {
 
 pub struct GamePlay<T, F> {
     player: Player<T>,
+    ghost: Option<Ghost<T>>,
+    trail: Vec<ghost::Frame>,
     world: World<T>,
+    // the file the level came from; playtests have none
+    level: Option<String>,
     viewport: ViewPort,
+    // where the viewport was before this update, to draw in between the two
+    previous_view: glm::IVec2,
+    alpha: f64,
+    follow: Follow,
     timer: TextBox<T, F, Duration>,
     score: TextBox<T, F, u32>,
+    lives: TextBox<T, F, u32>,
+    effects: Effects<T>,
+    health: u32,
+    spawn: glm::DVec2,
     splashes: Vec<Splash<T>>,
     splash_font: Rc<F>,
     finish: super::finish::Data<F>,
     heart: Heart<T>,
     time_up_font: Rc<F>,
+    pause: Pause<T>,
     state: State<T, F>,
+    source: Source,
+    outcome: Option<Outcome>,
+    // what happened during the last update that can be heard
+    sounds: Vec<Sound>,
 }
 
 pub struct Data<T> {
-    world: world::Data<T>,
-    game: data::Game,
+    campaign: Rc<data::Campaign>,
+    // shared with the level editor when playtesting
+    game: Rc<data::Game>,
     heart: Rc<T>,
 }
 
 impl<T> Data<T> {
     pub fn load<'t, TL>(
         texture_manager: &mut TextureManager<'t, TL>,
-        level: &data::Level,
-        game: data::Game,
+        campaign: Rc<data::Campaign>,
+        game: Rc<data::Game>,
     ) -> Result<Self>
     where
         T: Texture,
         TL: TextureLoader<'t, Texture = T>,
     {
-        let world = world::Data::load(texture_manager, level, &game)?;
         let heart = game.heart.texture.load(texture_manager)?;
-        Ok(Data { game, world, heart })
+        Ok(Data {
+            campaign,
+            game,
+            heart,
+        })
     }
 
     pub fn activate<'t, TL, FM>(
         &self,
         texture_manager: &mut TextureManager<'t, TL>,
         font_manager: &mut FM,
+        attempt: Attempt,
+    ) -> Result<GamePlay<T, FM::Font>>
+    where
+        TL: TextureLoader<'t, Texture = T>,
+        TL::Texture: Texture,
+        FM: font::Manager,
+        FM::Font: Font<Texture = T>,
+    {
+        let Attempt {
+            player: kind,
+            level,
+            ..
+        } = attempt;
+        let level_file = &self.campaign.levels[level].file;
+        let (player, npc) = self.players(kind);
+        let ghost = match ghost::Run::load(level_file, kind) {
+            Some(run) if attempt.ghost => Some(Ghost::load(player, run, texture_manager)?),
+            _ => None,
+        };
+        let path = self.campaign.levels[level].path();
+        let world = {
+            let level = data::Level::load(&path)
+                .chain_err(|| format!("cannot load level in path: {:?}", path))?;
+            world::Data::load(texture_manager, &level, &self.game)?
+        };
+        let spawn = match world.spawn {
+            Some(bl) => glm::ivec2(bl.x, bl.y - player.out_size.y as i32),
+            None => {
+                let (x, y) = data::DEFAULT_SPAWN;
+                glm::ivec2(x as i32, world.size.y - y as i32)
+            }
+        };
+        let mut game_play = {
+            let time_limit = world.time_limit;
+            let world = world.activate(npc, texture_manager)?;
+            self.start(texture_manager, font_manager, attempt, world, spawn, time_limit)
+        }?;
+        game_play.ghost = ghost;
+        game_play.level = Some(path);
+        game_play.source = Source::Record(Recording::new(level_file.clone(), kind));
+        Ok(game_play)
+    }
+
+    // drops the player into a level that is not part of the campaign, centered on `center`
+    pub fn playtest<'t, TL, FM>(
+        &self,
+        texture_manager: &mut TextureManager<'t, TL>,
+        font_manager: &mut FM,
+        world: &world::Data<T>,
         kind: PlayerKind,
+        center: glm::IVec2,
     ) -> Result<GamePlay<T, FM::Font>>
     where
         TL: TextureLoader<'t, Texture = T>,
@@ -122,16 +253,106 @@ impl<T> Data<T> {
         FM: font::Manager,
         FM::Font: Font<Texture = T>,
     {
-        let (player, npc) = match kind {
+        let (player, npc) = self.players(kind);
+        let time_limit = world.time_limit;
+        let world = world.activate(npc, texture_manager)?;
+        let spawn = {
+            let size: glm::IVec2 = player.out_size.into();
+            center - size / 2
+        };
+        // the run ends before the finish screen so the level index is never used
+        let attempt = Attempt {
+            player: kind,
+            level: 0,
+            ghost: false,
+        };
+        self.start(texture_manager, font_manager, attempt, world, spawn, time_limit)
+    }
+
+    // the same campaign with different game data
+    pub fn with_game<'t, TL>(
+        &self,
+        texture_manager: &mut TextureManager<'t, TL>,
+        game: data::Game,
+    ) -> Result<Self>
+    where
+        T: Texture,
+        TL: TextureLoader<'t, Texture = T>,
+    {
+        Data::load(texture_manager, Rc::clone(&self.campaign), Rc::new(game))
+    }
+
+    // brings a run that is already going up to date with what changed on disk; only a
+    // change to its own level file starts the level over, otherwise everything takes on
+    // its new look and keeps what happened so far
+    pub fn reload<'t, TL, F>(
+        &self,
+        texture_manager: &mut TextureManager<'t, TL>,
+        game_play: &mut GamePlay<T, F>,
+        changes: &Changes,
+    ) -> Result<()>
+    where
+        T: Texture,
+        TL: TextureLoader<'t, Texture = T>,
+    {
+        let restart = game_play
+            .level
+            .as_ref()
+            .map_or(false, |l| changes.has_level(l));
+        if !restart && !changes.game && !changes.sprites {
+            return Ok(());
+        }
+        let (player, npc) = self.players(game_play.finish.attempt.player);
+        if let Some(ref path) = game_play.level {
+            let level = data::Level::load(path)
+                .chain_err(|| format!("cannot load level in path: {:?}", path))?;
+            let world = world::Data::load(texture_manager, &level, &self.game)?;
+            if restart {
+                game_play.world = world.activate(npc, texture_manager)?;
+            } else {
+                game_play.world.refresh(&world, npc, texture_manager)?;
+            }
+        }
+        game_play.player.reload(player, texture_manager)?;
+        game_play.follow.reset(self.game.camera, game_play.world.size);
+        game_play.world.watch(&game_play.viewport);
+        game_play.heart.texture = Rc::clone(&self.heart);
+        game_play.heart.size = self.game.heart.out_size.into();
+        Ok(())
+    }
+
+    fn players(&self, kind: PlayerKind) -> (&data::Player, &data::Player) {
+        match kind {
             PlayerKind::Duck => (&self.game.duck, &self.game.husky),
             PlayerKind::Husky => (&self.game.husky, &self.game.duck),
-        };
-        let player = Player::load(player, glm::uvec2(150, 400), texture_manager)?;
-        let world = self.world.activate(npc, texture_manager)?;
+        }
+    }
+
+    fn start<'t, TL, FM>(
+        &self,
+        texture_manager: &mut TextureManager<'t, TL>,
+        font_manager: &mut FM,
+        attempt: Attempt,
+        world: World<T>,
+        spawn: glm::IVec2,
+        time_limit: Duration,
+    ) -> Result<GamePlay<T, FM::Font>>
+    where
+        TL: TextureLoader<'t, Texture = T>,
+        TL::Texture: Texture,
+        FM: font::Manager,
+        FM::Font: Font<Texture = T>,
+    {
+        let spawn = glm::uvec2(cmp::max(spawn.x, 0) as u32, cmp::max(spawn.y, 0) as u32);
+        let player = {
+            let (player, _) = self.players(attempt.player);
+            Player::load(player, spawn, texture_manager)
+        }?;
         let viewport = ViewPort::new(glm::ivec2(1280, 720));
+        let follow = Follow::new(self.game.camera, world.size);
         let font = font_manager.load(font::Kind::KenPixel, 32)?;
         let timer = TextBox::load(
-            Duration::from_secs(110),
+            time_limit,
             Rc::clone(&font),
             Box::new(|v| format!("Time: {:03}", v)),
         )?;
@@ -140,9 +361,19 @@ impl<T> Data<T> {
             Rc::clone(&font),
             Box::new(|s| format!("Score: {:05}", s)),
         )?;
+        let lives = TextBox::load(
+            LIVES,
+            Rc::clone(&font),
+            Box::new(|l| format!("Lives: {}", l)),
+        )?;
+        let effects = Effects::load(texture_manager, &self.game.power_ups)?;
         let splashes = vec![];
         let splash_font = font_manager.load(font::Kind::KenPixel, 24)?;
         let time_up_font = font_manager.load(font::Kind::KenPixel, 64)?;
+        let pause = {
+            let font = font_manager.load(font::Kind::KenPixel, 48)?;
+            Pause::load(&*time_up_font, &*font)?
+        };
         let finish = {
             let x_size = 1080;
             let y_size = 360;
@@ -150,6 +381,9 @@ impl<T> Data<T> {
                 title_font: font_manager.load(font::Kind::KenPixel, 48)?,
                 detail_font: font_manager.load(font::Kind::Joystix, 36)?,
                 view: glm::ivec4(640 - x_size / 2, 360 - y_size / 2, x_size, y_size),
+                time_limit,
+                campaign: Rc::clone(&self.campaign),
+                attempt,
             }
         };
         let heart = Heart {
@@ -158,39 +392,101 @@ impl<T> Data<T> {
             zoom: 0.,
         };
 
-        Ok(GamePlay {
+        let mut game_play = GamePlay {
             player,
+            ghost: None,
+            trail: vec![],
             world,
+            level: None,
             viewport,
+            previous_view: glm::ivec2(0, 0),
+            alpha: 1.,
+            follow,
             timer,
             score,
+            lives,
+            effects,
+            health: MAX_HEALTH,
+            spawn: glm::to_dvec2(spawn),
             splashes,
             splash_font,
             finish,
             time_up_font,
+            pause,
             heart,
             state: State::Running,
-        })
+            source: Source::Live,
+            outcome: None,
+            sounds: vec![],
+        };
+        game_play.snap_to_player();
+        Ok(game_play)
+    }
+
+    pub fn replay<'t, TL, FM>(
+        &self,
+        texture_manager: &mut TextureManager<'t, TL>,
+        font_manager: &mut FM,
+        recording: Recording,
+    ) -> Result<GamePlay<T, FM::Font>>
+    where
+        TL: TextureLoader<'t, Texture = T>,
+        TL::Texture: Texture,
+        FM: font::Manager,
+        FM::Font: Font<Texture = T>,
+    {
+        let level = self.campaign
+            .levels
+            .iter()
+            .position(|l| l.file == recording.level)
+            .ok_or_else(|| format!("replay level {:?} is not in the campaign", recording.level))?;
+        let attempt = Attempt {
+            level,
+            player: recording.player,
+            ghost: false,
+        };
+        let mut game_play = self.activate(texture_manager, font_manager, attempt)?;
+        game_play.source = Source::Replay(recording.playback());
+        Ok(game_play)
     }
 }
 
 impl<T, F: Font<Texture = T>> GamePlay<T, F> {
-    pub fn update(&mut self, delta: Duration, input: &input::State) -> Option<super::Kind>
+    pub fn update(&mut self, delta: Duration, actions: &Snapshot) -> Option<super::Kind>
     where
         T: Texture,
     {
-        self.splashes.retain(|s| s.is_active());
-        for s in &mut self.splashes {
-            s.update(delta);
+        self.remember();
+        let choice = match self.state {
+            State::Paused(ref mut p) => {
+                let choice = p.update(actions);
+                if p.navigated {
+                    self.sounds.push(Sound::Navigate);
+                }
+                Some(choice)
+            }
+            _ => None,
+        };
+        if let Some(choice) = choice {
+            return self.choose(choice);
         }
 
+        self.update_splashes(delta);
+
         match self.state {
             State::Running => {
-                self.update_running(delta, input);
+                if actions.did_press(Action::Pause) && !self.source.is_replay() {
+                    self.state = State::Paused(self.pause.clone());
+                    return None;
+                }
+                let keys = self.source.next(*actions);
+                self.update_running(delta, &keys);
                 None
             }
             State::Transition => {
-                if self.heart.zoom >= 1. {
+                if self.heart.zoom >= 1. && !self.source.is_recording() {
+                    return Some(super::Kind::Menu);
+                } else if self.heart.zoom >= 1. {
                     self.state = State::Finished(
                         super::finish::Finish::load(
                             &self.finish,
@@ -201,14 +497,19 @@ impl<T, F: Font<Texture = T>> GamePlay<T, F> {
                 } else if (self.player.dst_rect.y + self.player.dst_rect.w) as i32
                     >= self.world.npc.bottom()
                 {
-                    self.heart.zoom += 0.05;
+                    self.heart.zoom += HEART_GROWTH * seconds(delta);
                 } else {
-                    self.player.dst_rect.y += 4.;
+                    self.player.dst_rect.y += SINK_SPEED * seconds(delta);
                 }
                 None
             }
-            State::Finished(ref mut f) => f.update(delta, input),
-            State::TimeUp { .. } => if input.did_press_key(Keycode::Return) {
+            State::Finished(ref mut f) => f.update(delta, actions),
+            State::Paused(_) => None,
+            State::Respawning(_) => {
+                self.update_respawn(delta);
+                None
+            }
+            State::Lost { .. } => if actions.did_press(Action::Confirm) {
                 Some(super::Kind::Menu)
             } else {
                 None
@@ -216,42 +517,162 @@ impl<T, F: Font<Texture = T>> GamePlay<T, F> {
         }
     }
 
-    pub fn update_running(&mut self, delta: Duration, input: &input::State)
+    fn choose(&mut self, choice: Option<Choice>) -> Option<super::Kind> {
+        match choice {
+            None => None,
+            Some(Choice::Resume) => {
+                self.state = State::Running;
+                None
+            }
+            Some(Choice::Restart) => Some(super::Kind::GamePlay(self.finish.attempt)),
+            Some(Choice::Quit) => Some(super::Kind::Menu),
+        }
+    }
+
+    // steps a replay without any live input; used when running headless
+    pub fn simulate(&mut self, delta: Duration) -> Option<Outcome>
     where
         T: Texture,
     {
-        self.world.update(delta);
+        self.remember();
+        self.update_splashes(delta);
+
+        match self.state {
+            State::Running => {
+                let keys = match self.source {
+                    Source::Replay(ref mut p) => p.next(),
+                    Source::Record(_) | Source::Live => Snapshot::default(),
+                };
+                self.update_running(delta, &keys);
+            }
+            State::Respawning(_) => self.update_respawn(delta),
+            _ => {}
+        }
+        self.outcome
+    }
+
+    pub fn is_input_exhausted(&self) -> bool {
+        match self.source {
+            Source::Replay(ref p) => p.is_done(),
+            Source::Record(_) | Source::Live => false,
+        }
+    }
 
-        self.player.process(input);
+    fn update_splashes(&mut self, delta: Duration) {
+        self.splashes.retain(|s| s.is_active());
+        for s in &mut self.splashes {
+            s.update(delta);
+        }
+    }
+
+    pub fn update_running(&mut self, delta: Duration, keys: &Snapshot)
+    where
+        T: Texture,
+    {
+        self.world.update(delta, Some(self.player.dst_rect.center()));
+
+        self.effects.update(delta);
+        self.player.double_jump = self.effects.is_active(data::PowerUpKind::DoubleJump);
+        self.player.boost = if self.effects.is_active(data::PowerUpKind::SpeedBoost) {
+            SPEED_BOOST
+        } else {
+            1.
+        };
+        self.player.process(keys, delta);
+        if self.player.jumped {
+            self.sounds.push(Sound::Jump);
+        }
         self.timer.update(delta);
         let (force, legs, touch_spikes) = self.world.force(&self.player);
+        let bottom = f64::from(self.world.size.y);
+        // once below the floor over a pit there is no climbing back out
+        let above_floor = self.player.dst_rect.y <= self.player.floor(bottom);
         self.player.update((force, legs), delta);
-        let center = {
-            let dst = self.player.dst_rect;
-            glm::ivec2((dst.x + dst.z / 2.) as i32, (dst.y + dst.w / 2.) as i32)
-        };
-        self.viewport.center(center);
+        if above_floor && !self.world.is_over_pit(&self.player) {
+            self.player.clamp_to_floor(bottom);
+        }
+        self.trail.push(self.player.ghost_frame());
+        if let Some(ref mut g) = self.ghost {
+            g.update(delta);
+        }
+        if self.effects.is_active(data::PowerUpKind::Magnet) {
+            let center = self.player.dst_rect.center();
+            self.world.attract(center, MAGNET_RANGE, MAGNET_SPEED * seconds(delta));
+        }
+        self.follow_player(delta);
+        {
+            let height = self.player.dst_rect.w;
+            let x = self.player.dst_rect.x;
+            if let Some(c) = self.world.reach_checkpoint(x) {
+                self.spawn = c.spawn(height);
+            }
+        }
+        let mut dead = self.player.dst_rect.y > bottom;
         {
             let body = self.player.body();
             let legs = self.player.legs();
 
             let color = ColorRGBA(0, 200, 125, 255);
+            let mut power_ups = vec![];
             for c in self.world
                 .collectables
                 .retain_or_drain(|c| !body.intersects(&c.body) && !legs.intersects(&c.body))
             {
+                let sound = match c.kind {
+                    collectable::Kind::Coin => Sound::Coin,
+                    // power ups are as rare as gems and sound like them
+                    collectable::Kind::Gem | collectable::Kind::PowerUp(_) => Sound::Gem,
+                };
+                self.sounds.push(sound);
+                if let collectable::Kind::PowerUp(k) = c.kind {
+                    power_ups.push(k);
+                }
+                if c.score == 0 {
+                    continue;
+                }
                 let texture = self.splash_font
                     .texturize(&format!("+{}", c.score), &color)
                     .unwrap();
                 let splash = Splash {
                     texture,
                     duration: Duration::from_secs(1),
+                    risen: 0.,
                     dst: glm::to_ivec2(c.body.center()).into(),
                 };
                 self.splashes.push(splash);
                 self.score.update(c.score as i32);
             }
 
+            // only counts as landing on an enemy if the feet were above its middle before
+            let falling = self.player.delta_pos.y > 0.;
+            let feet = self.player.dst_rect.y + self.player.dst_rect.w - self.player.step.y;
+            let mut stomped = false;
+            for e in self.world.enemies.retain_or_drain(|e| {
+                e.stomp.is_none() || !falling || feet > e.dst.center().y
+                    || !e.body().collides(&legs)
+            }) {
+                let award = e.stomp.unwrap_or(0);
+                let texture = self.splash_font
+                    .texturize(&format!("+{}", award), &color)
+                    .unwrap();
+                let splash = Splash {
+                    texture,
+                    duration: Duration::from_secs(1),
+                    risen: 0.,
+                    dst: glm::to_ivec2(e.dst.center()).into(),
+                };
+                self.splashes.push(splash);
+                self.score.update(award as i32);
+                stomped = true;
+            }
+            if stomped {
+                self.sounds.push(Sound::Cat);
+                self.player.bounce();
+            }
+            for k in power_ups {
+                self.power_up(k);
+            }
+
             let dmg = if self.player.invincibility.is_some() {
                 None
             } else if self.world
@@ -260,6 +681,7 @@ impl<T, F: Font<Texture = T>> GamePlay<T, F> {
                 .map(|e| e.body())
                 .any(|b| b.collides(&body) || b.collides(&legs))
             {
+                self.sounds.push(Sound::Cat);
                 Some(25)
             } else if touch_spikes {
                 Some(50)
@@ -268,6 +690,7 @@ impl<T, F: Font<Texture = T>> GamePlay<T, F> {
             };
 
             if let Some(d) = dmg {
+                self.sounds.push(Sound::Damage);
                 let dmg = -d;
                 self.player.invincible();
                 let color = ColorRGBA(255, 0, 0, 255);
@@ -277,29 +700,166 @@ impl<T, F: Font<Texture = T>> GamePlay<T, F> {
                 let splash = Splash {
                     texture,
                     duration: Duration::from_secs(1),
+                    risen: 0.,
                     dst: glm::to_ivec2(self.player.dst_rect.center()).into(),
                 };
                 self.splashes.push(splash);
                 self.score.update(dmg);
+                self.health -= 1;
+                dead |= self.health == 0;
             }
         }
-        if (self.player.dst_rect.x + self.player.dst_rect.z) as i32 >= self.world.npc.x() {
+        if dead {
+            self.lose_life();
+        } else if (self.player.dst_rect.x + self.player.dst_rect.z) as i32 >= self.world.npc.x() {
             self.player.invincibility = None;
+            self.sounds.push(Sound::Complete);
             self.state = State::Transition;
+        } else if self.timer.value.as_secs() == 0 && self.timer.value.subsec_nanos() == 0 {
+            self.lose("TIME'S UP!");
         }
-        if self.timer.value.as_secs() == 0 && self.timer.value.subsec_nanos() == 0 {
-            self.player.invincibility = None;
-            let x_size = 800;
-            let y_size = 200;
-            self.state = State::TimeUp {
-                view: glm::ivec4(640 - x_size / 2, 360 - y_size / 2, x_size, y_size),
-                title: self.time_up_font
-                    .texturize("TIME'S UP!", &ColorRGBA(255, 0, 0, 255))
-                    .unwrap(),
-                instructions: self.time_up_font
-                    .texturize("<PRESS ENTER>", &ColorRGBA(255, 255, 255, 255))
-                    .unwrap(),
-            };
+
+        let finished = match self.state {
+            State::Running | State::Respawning(_) => return,
+            State::Transition => true,
+            _ => false,
+        };
+        self.end_run(finished);
+    }
+
+    fn follow_player(&mut self, delta: Duration) {
+        let center = self.player.dst_rect.center();
+        let backwards = self.player.is_backwards();
+        self.follow.update(center, backwards, delta, &mut self.viewport);
+        self.world.watch(&self.viewport);
+    }
+
+    fn snap_to_player(&mut self) {
+        let center = self.player.dst_rect.center();
+        let backwards = self.player.is_backwards();
+        self.follow.snap(center, backwards, &mut self.viewport);
+        self.world.watch(&self.viewport);
+        self.previous_view = self.viewport.translation();
+    }
+
+    fn remember(&mut self) {
+        self.sounds.clear();
+        self.player.remember();
+        self.world.remember();
+        self.previous_view = self.viewport.translation();
+    }
+
+    pub fn sounds(&self) -> &[Sound] {
+        &self.sounds
+    }
+
+    pub fn music(&self) -> Music {
+        match self.state {
+            State::Finished(_) => Music::Finish,
+            _ => Music::GamePlay,
+        }
+    }
+
+    // how far into the next update the frame being drawn is, from 0 to 1
+    pub fn interpolate(&mut self, alpha: f64) {
+        self.alpha = alpha;
+        self.player.interpolate(alpha);
+        self.world.interpolate(alpha);
+        if let Some(ref mut g) = self.ghost {
+            g.interpolate(alpha);
+        }
+    }
+
+    fn power_up(&mut self, kind: data::PowerUpKind) {
+        let duration = self.effects.start(kind);
+        match kind {
+            data::PowerUpKind::Invincibility => self.player.invincible_for(duration),
+            data::PowerUpKind::ExtraTime => self.timer.value += duration,
+            _ => {}
+        }
+    }
+
+    fn lose_life(&mut self) {
+        self.effects.clear();
+        self.lives.update(-1);
+        if self.lives.value == 0 {
+            self.lose("GAME OVER");
+        } else {
+            self.state = State::Respawning(Duration::from_millis(FADE_MILLIS));
+        }
+    }
+
+    fn lose(&mut self, title: &str) {
+        self.player.invincibility = None;
+        let x_size = 800;
+        let y_size = 200;
+        self.state = State::Lost {
+            view: glm::ivec4(640 - x_size / 2, 360 - y_size / 2, x_size, y_size),
+            title: self.time_up_font
+                .texturize(title, &ColorRGBA(255, 0, 0, 255))
+                .unwrap(),
+            instructions: self.time_up_font
+                .texturize("<PRESS ENTER>", &ColorRGBA(255, 255, 255, 255))
+                .unwrap(),
+        };
+    }
+
+    // the player is moved back to the last checkpoint while the screen is dark
+    fn update_respawn(&mut self, delta: Duration) {
+        let remaining = match self.state {
+            State::Respawning(r) => r,
+            _ => return,
+        };
+        let half = Duration::from_millis(FADE_MILLIS / 2);
+        let next = remaining.checked_sub(delta).unwrap_or_default();
+        if remaining > half && next <= half {
+            self.player.respawn(self.spawn);
+            self.health = MAX_HEALTH;
+            self.snap_to_player();
+        }
+        self.state = if next == Duration::default() {
+            State::Running
+        } else {
+            State::Respawning(next)
+        };
+    }
+
+    fn end_run(&mut self, finished: bool) {
+        let outcome = Outcome {
+            finished,
+            score: self.score.value,
+            remaining: self.timer.value,
+        };
+        match self.source {
+            Source::Record(ref r) => {
+                if let Err(e) = r.save(replay::last_run()) {
+                    println!("could not save replay: {:?}", e);
+                }
+                if finished {
+                    let score = outcome.score + super::finish::time_bonus(outcome.remaining);
+                    let mut progress = progress::get();
+                    progress.complete(&self.finish.campaign, self.finish.attempt.level, score);
+                    if let Err(e) = progress::create(&progress) {
+                        println!("could not save progress: {:?}", e);
+                    }
+                    let run = ghost::Run {
+                        score,
+                        frames: mem::replace(&mut self.trail, vec![]),
+                    };
+                    if let Err(e) = run.save_if_best(&r.level, r.player) {
+                        println!("could not save ghost: {:?}", e);
+                    }
+                }
+            }
+            Source::Replay(_) | Source::Live => println!("{}", outcome),
+        }
+        self.outcome = Some(outcome);
+    }
+
+    pub fn is_running(&self) -> bool {
+        match self.state {
+            State::Running | State::Respawning(_) => true,
+            _ => false,
         }
     }
 
@@ -308,6 +868,7 @@ impl<T, F: Font<Texture = T>> GamePlay<T, F> {
             f.before_draw()?;
         }
         self.score.before_draw()?;
+        self.lives.before_draw()?;
         self.timer.before_draw()
     }
 }
@@ -318,8 +879,12 @@ where
 {
     fn show(&self, renderer: &mut R) -> moho::errors::Result<()> {
         {
-            let mut renderer = self.viewport.camera(renderer);
+            let viewport = self.viewport.blend(self.previous_view, self.alpha);
+            let mut renderer = viewport.camera(renderer);
             renderer.show(&self.world)?;
+            if let Some(ref g) = self.ghost {
+                renderer.show(g)?;
+            }
             renderer.show(&self.player)?;
             self.splashes.iter().map(|s| renderer.show(s)).try()?;
 
@@ -334,29 +899,40 @@ where
         }
 
         renderer.copy_asset(&self.score, options::at(align::top(0).center(320)))?;
+        renderer.copy_asset(&self.lives, options::at(align::top(0).center(640)))?;
         renderer.copy_asset(&self.timer, options::at(align::top(0).center(960)))?;
+        {
+            let size = self.heart.size / 2;
+            let left = 640 - (size.x * MAX_HEALTH) as i32 / 2;
+            (0..self.health)
+                .map(|i| {
+                    let dst = align::top(40)
+                        .left(left + (size.x * i) as i32)
+                        .dims(size);
+                    renderer.copy(&*self.heart.texture, options::at(dst))
+                })
+                .try()?;
+        }
+        renderer.show(&self.effects)?;
+
+        if let State::Respawning(remaining) = self.state {
+            let millis =
+                remaining.as_secs() * 1000 + u64::from(remaining.subsec_nanos()) / 1_000_000;
+            let progress = millis as f64 / FADE_MILLIS as f64;
+            let alpha = (1. - (2. * progress - 1.).abs()) * 255.;
+            renderer.set_draw_color(ColorRGBA(0, 0, 0, alpha as u8));
+            renderer.fill_rects(&[Rect::new(0, 0, 1280, 720)])?;
+        }
 
         match self.state {
             State::Finished(ref f) => renderer.show(f),
-            State::TimeUp {
+            State::Paused(ref p) => renderer.show(p),
+            State::Lost {
                 ref title,
                 ref instructions,
                 ref view,
             } => {
-                //border
-                renderer.set_draw_color(ColorRGBA(0, 0, 0, 255));
-                renderer.fill_rects(&[Rect::new(view.x, view.y, view.z as u32, view.w as u32)])?;
-                //background
-                renderer.set_draw_color(ColorRGBA(60, 0, 70, 255));
-                renderer.fill_rects(&[
-                    Rect::new(
-                        view.x + 6,
-                        view.y + 6,
-                        view.z as u32 - 12,
-                        view.w as u32 - 12,
-                    ),
-                ])?;
-
+                super::show_frame(renderer, view)?;
                 renderer.copy(title, options::at(align::bottom(360).center(640)))?;
                 renderer.copy(instructions, options::at(align::top(360).center(640)))
             }
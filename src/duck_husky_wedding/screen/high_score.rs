@@ -1,27 +1,37 @@
+use data;
+use duck_husky_wedding::controls::{Action, Snapshot};
 use duck_husky_wedding::font;
 use duck_husky_wedding::high_score;
 use utils::Try;
 use errors::*;
 
-use moho::{self, input};
+use moho;
 use moho::renderer::{align, options, ColorRGBA, Font, Renderer, Scene, Texture};
-use sdl2::keyboard::Keycode;
 
 use std::rc::Rc;
 
+static PLAYERS: [super::PlayerKind; 2] = [super::PlayerKind::Husky, super::PlayerKind::Duck];
+
+struct Page<T> {
+    header: T,
+    scores: Vec<T>,
+}
+
 pub struct HighScore<T> {
     title: Rc<T>,
     instructions: Rc<T>,
-    scores: Vec<T>,
+    pages: Vec<Page<T>>,
+    current: usize,
 }
 
 pub struct Data<T> {
     title: Rc<T>,
     instructions: Rc<T>,
+    campaign: Rc<data::Campaign>,
 }
 
 impl<T> Data<T> {
-    pub fn load<FM>(font_manager: &mut FM) -> Result<Self>
+    pub fn load<FM>(font_manager: &mut FM, campaign: Rc<data::Campaign>) -> Result<Self>
     where
         FM: font::Manager,
         FM::Font: Font<Texture = T>,
@@ -34,7 +44,7 @@ impl<T> Data<T> {
             font.texturize(text, &color).map(Rc::new)
         }?;
         let instructions = {
-            let text = "<PRESS ENTER TO GO TO MAIN MENU>";
+            let text = "<LEFT/RIGHT TO CHANGE TABLE; ENTER TO GO TO MAIN MENU>";
             let font = font_manager.load(font::Kind::KenPixel, 32)?;
             font.texturize(text, &color).map(Rc::new)
         }?;
@@ -42,26 +52,67 @@ impl<T> Data<T> {
         Ok(Data {
             title,
             instructions,
+            campaign,
         })
     }
 
-    pub fn activate<FM>(&mut self, font_manager: &mut FM) -> Result<HighScore<T>>
+    pub fn activate<FM>(
+        &mut self,
+        font_manager: &mut FM,
+        level: usize,
+        player: super::PlayerKind,
+    ) -> Result<HighScore<T>>
     where
         FM: font::Manager,
         FM::Font: Font<Texture = T>,
     {
-        let font = font_manager.load(font::Kind::Joystix, 32)?;
+        let header_font = font_manager.load(font::Kind::KenPixel, 48)?;
+        let font = font_manager.load(font::Kind::Joystix, 28)?;
+        let header_color = ColorRGBA(255, 255, 0, 255);
         let color = ColorRGBA(255, 255, 255, 255);
+        let high_scores = high_score::get();
 
-        let scores = high_score::get()
+        let pages = self.campaign
+            .levels
             .iter()
-            .map(|s| {
-                let score = format!("{:06}{:5}{:>6}", s.score, "", s.name);
-                font.texturize(&score, &color).map_err(Into::into)
+            .flat_map(|l| PLAYERS.iter().map(move |&p| (l, p)))
+            .map(|(l, p)| -> Result<Page<T>> {
+                let character = match p {
+                    super::PlayerKind::Husky => "Husky",
+                    super::PlayerKind::Duck => "Duck",
+                };
+                let header = header_font
+                    .texturize(&format!("< {} - {} >", l.name, character), &header_color)?;
+                let table = high_scores.table(&l.file, p);
+                let scores = if table.is_empty() {
+                    vec![font.texturize("NO SCORES YET", &color)?]
+                } else {
+                    table
+                        .iter()
+                        .map(|s| {
+                            let score = format!(
+                                "{:06}  {:<6}  {:>3}s  {}",
+                                s.score,
+                                s.name,
+                                s.time.as_secs(),
+                                s.formatted_date()
+                            );
+                            font.texturize(&score, &color).map_err(Into::into)
+                        })
+                        .collect::<Result<Vec<_>>>()?
+                };
+                Ok(Page { header, scores })
             })
             .collect::<Result<Vec<_>>>()?;
+
+        let current = PLAYERS
+            .iter()
+            .position(|&p| p == player)
+            .map_or(0, |p| level * PLAYERS.len() + p);
+
         Ok(HighScore {
-            scores: scores,
+            pages,
+            current,
             title: Rc::clone(&self.title),
             instructions: Rc::clone(&self.instructions),
         })
@@ -69,8 +120,18 @@ impl<T> Data<T> {
 }
 
 impl<T> HighScore<T> {
-    pub fn update(&mut self, input: &input::State) -> Option<super::Kind> {
-        if input.did_press_key(Keycode::Return) {
+    pub fn update(&mut self, actions: &Snapshot) -> Option<super::Kind> {
+        let left = actions.did_press(Action::MoveLeft);
+        let right = actions.did_press(Action::MoveRight);
+        let count = self.pages.len();
+
+        if right && !left {
+            self.current = (self.current + 1) % count;
+        } else if left && !right {
+            self.current = (self.current + count - 1) % count;
+        }
+
+        if actions.did_press(Action::Confirm) || actions.did_press(Action::Back) {
             Some(super::Kind::Menu)
         } else {
             None
@@ -93,11 +154,14 @@ where
             renderer.copy(texture, options::at(dst))
         }?;
 
-        self.scores
+        let page = &self.pages[self.current];
+        renderer.copy(&page.header, options::at(center.top(90)))?;
+
+        page.scores
             .iter()
             .enumerate()
             .map(|(i, s)| ((s.dims().y * i as u32) as i32, s))
-            .map(|(d, s)| renderer.copy(s, options::at(center.top(150 + d))))
+            .map(|(d, s)| renderer.copy(s, options::at(center.top(160 + d))))
             .try()
     }
 }
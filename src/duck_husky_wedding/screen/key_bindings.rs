@@ -0,0 +1,197 @@
+use duck_husky_wedding::controls::{self, Bindings};
+use duck_husky_wedding::font;
+use utils::Try;
+use errors::*;
+
+use moho::{self, input};
+use moho::renderer::{align, options, ColorRGBA, Font, Renderer, Scene, Texture};
+use sdl2::keyboard::Keycode;
+
+use std::rc::Rc;
+
+struct Row<T> {
+    label: T,
+    keys: T,
+}
+
+pub struct KeyBindings<T, F> {
+    title: Rc<T>,
+    instructions: Rc<T>,
+    waiting: Rc<T>,
+    picker: Rc<T>,
+    font: Rc<F>,
+    rows: Vec<Row<T>>,
+    bindings: Bindings,
+    selected: usize,
+    listening: bool,
+    // the row rebound since it was last drawn
+    changed: Option<usize>,
+}
+
+pub struct Data<T> {
+    title: Rc<T>,
+    instructions: Rc<T>,
+    waiting: Rc<T>,
+    picker: Rc<T>,
+}
+
+fn key_names(bindings: &Bindings, action: controls::Action) -> String {
+    bindings
+        .keys(action)
+        .iter()
+        .map(|k| k.name())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl<T> Data<T> {
+    pub fn load<FM>(font_manager: &mut FM, picker: Rc<T>) -> Result<Self>
+    where
+        FM: font::Manager,
+        FM::Font: Font<Texture = T>,
+    {
+        let color = ColorRGBA(255, 255, 0, 255);
+
+        let title = {
+            let font = font_manager.load(font::Kind::KenPixel, 64)?;
+            font.texturize("Controls", &color).map(Rc::new)
+        }?;
+        let instructions = {
+            let text = "<Arrow Keys to choose; Enter to rebind; Escape to save and go back>";
+            let font = font_manager.load(font::Kind::KenPixel, 32)?;
+            font.texturize(text, &color).map(Rc::new)
+        }?;
+        let waiting = {
+            let font = font_manager.load(font::Kind::KenPixel, 32)?;
+            font.texturize("PRESS A KEY", &ColorRGBA(255, 255, 255, 255))
+                .map(Rc::new)
+        }?;
+
+        Ok(Data {
+            title,
+            instructions,
+            waiting,
+            picker,
+        })
+    }
+
+    pub fn activate<FM>(
+        &self,
+        font_manager: &mut FM,
+        bindings: &Bindings,
+    ) -> Result<KeyBindings<T, FM::Font>>
+    where
+        FM: font::Manager,
+        FM::Font: Font<Texture = T>,
+    {
+        let font = font_manager.load(font::Kind::KenPixel, 40)?;
+        let color = ColorRGBA(255, 255, 255, 255);
+        let rows = controls::ACTIONS
+            .iter()
+            .map(|&a| -> Result<Row<T>> {
+                let label = font.texturize(a.label(), &color)?;
+                let keys = font.texturize(&key_names(bindings, a), &color)?;
+                Ok(Row { label, keys })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(KeyBindings {
+            rows,
+            font,
+            bindings: bindings.clone(),
+            selected: 0,
+            listening: false,
+            changed: None,
+            title: Rc::clone(&self.title),
+            instructions: Rc::clone(&self.instructions),
+            waiting: Rc::clone(&self.waiting),
+            picker: Rc::clone(&self.picker),
+        })
+    }
+}
+
+impl<T, F: Font<Texture = T>> KeyBindings<T, F> {
+    pub fn bindings(&self) -> &Bindings {
+        &self.bindings
+    }
+
+    // navigation here ignores the bindings so a bad one can always be undone
+    pub fn update(&mut self, input: &input::State) -> Option<super::Kind> {
+        if self.listening {
+            let action = controls::ACTIONS[self.selected];
+            if let Some(&k) = controls::BINDABLE.iter().find(|&&k| input.did_press_key(k)) {
+                self.bindings.bind(action, k);
+                self.listening = false;
+                self.changed = Some(self.selected);
+            }
+            return None;
+        }
+
+        let down = input.did_press_key(Keycode::Down);
+        let up = input.did_press_key(Keycode::Up);
+        let count = self.rows.len();
+
+        if down && !up {
+            self.selected = (self.selected + 1) % count;
+        } else if up && !down {
+            self.selected = (self.selected + count - 1) % count;
+        }
+
+        if input.did_press_key(Keycode::Escape) {
+            if let Err(e) = controls::create(&self.bindings) {
+                println!("could not save controls: {:?}", e);
+            }
+            Some(super::Kind::Menu)
+        } else {
+            if input.did_press_key(Keycode::Return) {
+                self.listening = true;
+            }
+            None
+        }
+    }
+
+    pub fn before_draw(&mut self) -> Result<()> {
+        if let Some(i) = self.changed.take() {
+            let action = controls::ACTIONS[i];
+            let color = ColorRGBA(255, 255, 255, 255);
+            self.rows[i].keys = self.font
+                .texturize(&key_names(&self.bindings, action), &color)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'t, R: Renderer<'t>, F> Scene<R> for KeyBindings<R::Texture, F>
+where
+    R::Texture: Texture,
+{
+    fn show(&self, renderer: &mut R) -> moho::errors::Result<()> {
+        let center = align::center(640);
+
+        renderer.copy(&*self.title, options::at(center.top(0)))?;
+
+        {
+            let texture = &*self.instructions;
+            let dst = center.bottom(720 - texture.dims().y as i32);
+            renderer.copy(texture, options::at(dst))
+        }?;
+
+        self.rows
+            .iter()
+            .enumerate()
+            .map(|(i, r)| -> moho::errors::Result<()> {
+                let middle = align::middle(140 + 65 * i as i32);
+                renderer.copy(&r.label, options::at(middle.left(300)))?;
+                if i != self.selected {
+                    return renderer.copy(&r.keys, options::at(middle.left(700)));
+                }
+                renderer.copy(&*self.picker, options::at(middle.right(290)))?;
+                if self.listening {
+                    renderer.copy(&*self.waiting, options::at(middle.left(700)))
+                } else {
+                    renderer.copy(&r.keys, options::at(middle.left(700)))
+                }
+            })
+            .try()
+    }
+}
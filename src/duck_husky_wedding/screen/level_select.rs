@@ -0,0 +1,168 @@
+use data;
+use duck_husky_wedding::controls::{Action, Snapshot};
+use duck_husky_wedding::font;
+use duck_husky_wedding::progress;
+use utils::Try;
+use errors::*;
+
+use moho;
+use moho::renderer::{align, options, ColorRGBA, Font, Renderer, Scene, Texture};
+
+use std::rc::Rc;
+
+struct Level<T> {
+    name: T,
+    score: Option<T>,
+    unlocked: bool,
+}
+
+pub struct LevelSelect<T> {
+    title: Rc<T>,
+    instructions: Rc<T>,
+    picker: Rc<T>,
+    levels: Vec<Level<T>>,
+    selected: usize,
+    pub navigated: bool,
+}
+
+pub struct Data<T> {
+    title: Rc<T>,
+    instructions: Rc<T>,
+    picker: Rc<T>,
+    campaign: Rc<data::Campaign>,
+}
+
+impl<T> Data<T> {
+    pub fn load<FM>(
+        font_manager: &mut FM,
+        campaign: Rc<data::Campaign>,
+        picker: Rc<T>,
+    ) -> Result<Self>
+    where
+        FM: font::Manager,
+        FM::Font: Font<Texture = T>,
+    {
+        let color = ColorRGBA(255, 255, 0, 255);
+
+        let title = {
+            let text = "Select Level";
+            let font = font_manager.load(font::Kind::KenPixel, 64)?;
+            font.texturize(text, &color).map(Rc::new)
+        }?;
+        let instructions = {
+            let text = "<Use Arrow Keys to choose level; Enter to play; Escape to go back>";
+            let font = font_manager.load(font::Kind::KenPixel, 32)?;
+            font.texturize(text, &color).map(Rc::new)
+        }?;
+
+        Ok(Data {
+            title,
+            instructions,
+            picker,
+            campaign,
+        })
+    }
+
+    pub fn activate<FM>(&self, font_manager: &mut FM) -> Result<LevelSelect<T>>
+    where
+        FM: font::Manager,
+        FM::Font: Font<Texture = T>,
+    {
+        let name_font = font_manager.load(font::Kind::KenPixel, 48)?;
+        let score_font = font_manager.load(font::Kind::Joystix, 32)?;
+        let unlocked_color = ColorRGBA(255, 255, 255, 255);
+        let locked_color = ColorRGBA(120, 120, 120, 255);
+        let progress = progress::get();
+
+        let levels = self.campaign
+            .levels
+            .iter()
+            .enumerate()
+            .map(|(i, l)| -> Result<Level<T>> {
+                let unlocked = progress.is_unlocked(&self.campaign, i);
+                let (text, color) = if unlocked {
+                    (l.name.clone(), &unlocked_color)
+                } else {
+                    (format!("{} (locked)", l.name), &locked_color)
+                };
+                let name = name_font.texturize(&text, color)?;
+                let score = match progress.level(&self.campaign, i).best_score {
+                    None => None,
+                    Some(s) => Some(score_font.texturize(&format!("{:06}", s), color)?),
+                };
+                Ok(Level {
+                    name,
+                    score,
+                    unlocked,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(LevelSelect {
+            levels,
+            selected: 0,
+            navigated: false,
+            title: Rc::clone(&self.title),
+            instructions: Rc::clone(&self.instructions),
+            picker: Rc::clone(&self.picker),
+        })
+    }
+}
+
+impl<T> LevelSelect<T> {
+    pub fn update(&mut self, actions: &Snapshot) -> Option<super::Kind> {
+        let down = actions.did_press(Action::MoveDown);
+        let up = actions.did_press(Action::Jump);
+        let count = self.levels.len();
+        let selected = self.selected;
+
+        if down && !up {
+            self.selected = (self.selected + 1) % count;
+        } else if up && !down {
+            self.selected = (self.selected + count - 1) % count;
+        }
+
+        let next = if actions.did_press(Action::Back) {
+            Some(super::Kind::Menu)
+        } else if actions.did_press(Action::Confirm) && self.levels[self.selected].unlocked {
+            Some(super::Kind::PlayerSelect(self.selected))
+        } else {
+            None
+        };
+        self.navigated = next.is_some() || selected != self.selected;
+        next
+    }
+}
+
+impl<'t, R: Renderer<'t>> Scene<R> for LevelSelect<R::Texture>
+where
+    R::Texture: Texture,
+{
+    fn show(&self, renderer: &mut R) -> moho::errors::Result<()> {
+        let center = align::center(640);
+
+        renderer.copy(&*self.title, options::at(center.top(0)))?;
+
+        {
+            let texture = &*self.instructions;
+            let dst = center.bottom(720 - texture.dims().y as i32);
+            renderer.copy(texture, options::at(dst))
+        }?;
+
+        self.levels
+            .iter()
+            .enumerate()
+            .map(|(i, l)| -> moho::errors::Result<()> {
+                let middle = align::middle(175 + 75 * i as i32);
+                if i == self.selected {
+                    renderer.copy(&*self.picker, options::at(middle.right(290)))?;
+                }
+                renderer.copy(&l.name, options::at(middle.left(300)))?;
+                match l.score {
+                    Some(ref s) => renderer.copy(s, options::at(middle.right(980))),
+                    None => Ok(()),
+                }
+            })
+            .try()
+    }
+}
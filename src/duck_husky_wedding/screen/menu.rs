@@ -1,14 +1,14 @@
 use data;
 use errors::*;
 use duck_husky_wedding::{button, font};
+use duck_husky_wedding::controls::{Action, Snapshot};
 
 use glm;
-use moho::{self, input};
+use moho;
 use moho::renderer::{align, options, ColorRGBA, Font, Renderer, Scene, Texture, TextureLoader,
                      TextureManager};
 
 use std::rc::Rc;
-use sdl2::keyboard::Keycode;
 
 pub struct Image<T> {
     texture: Rc<T>,
@@ -30,6 +30,7 @@ pub struct Menu<T> {
     heart: Image<T>,
     button_manager: ButtonManager<T>,
     instructions: Rc<T>,
+    pub navigated: bool,
 }
 
 impl<T> Clone for Menu<T> {
@@ -40,6 +41,7 @@ impl<T> Clone for Menu<T> {
             husky: self.husky.clone(),
             heart: self.heart.clone(),
             instructions: Rc::clone(&self.instructions),
+            navigated: self.navigated,
         }
     }
 }
@@ -102,15 +104,22 @@ impl<T> Menu<T> {
             heart,
             button_manager,
             instructions,
+            navigated: false,
         })
     }
 }
 
 impl<T> Menu<T> {
-    pub fn update(&mut self, input: &input::State) -> Option<super::Kind> {
-        self.button_manager.update(input).map(|b| match b {
-            ButtonKind::HighScore => super::Kind::HighScore,
-            ButtonKind::NewGame => super::Kind::PlayerSelect,
+    pub fn update(&mut self, actions: &Snapshot) -> Option<super::Kind> {
+        let selected = self.button_manager.selected;
+        let next = self.button_manager.update(actions);
+        self.navigated = next.is_some() || selected != self.button_manager.selected;
+        next.map(|b| match b {
+            ButtonKind::HighScore => super::Kind::HighScore(0, super::PlayerKind::Husky),
+            ButtonKind::NewGame => super::Kind::PlayerSelect(0),
+            ButtonKind::LevelSelect => super::Kind::LevelSelect,
+            ButtonKind::KeyBindings => super::Kind::KeyBindings,
+            ButtonKind::Options => super::Kind::Options,
         })
     }
 }
@@ -137,7 +146,10 @@ where
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum ButtonKind {
     NewGame,
+    LevelSelect,
     HighScore,
+    KeyBindings,
+    Options,
 }
 
 struct Button<T> {
@@ -159,7 +171,10 @@ impl<T> Clone for Button<T> {
 struct ButtonManager<T> {
     selected: ButtonKind,
     new_game: Button<T>,
+    level_select: Button<T>,
     high_score: Button<T>,
+    key_bindings: Button<T>,
+    options: Button<T>,
     picker: Rc<T>,
 }
 
@@ -168,7 +183,10 @@ impl<T> Clone for ButtonManager<T> {
         ButtonManager {
             selected: self.selected,
             new_game: self.new_game.clone(),
+            level_select: self.level_select.clone(),
             high_score: self.high_score.clone(),
+            key_bindings: self.key_bindings.clone(),
+            options: self.options.clone(),
             picker: Rc::clone(&self.picker),
         }
     }
@@ -180,7 +198,7 @@ impl<T> ButtonManager<T> {
         F: Font<Texture = T>,
     {
         let new_game = {
-            let center = glm::ivec2(640, 325);
+            let center = glm::ivec2(640, 260);
             let inner = button::Static::with_text("New Game", font)?;
             Button {
                 center,
@@ -189,8 +207,18 @@ impl<T> ButtonManager<T> {
             }
         };
 
+        let level_select = {
+            let center = glm::ivec2(640, 340);
+            let inner = button::Static::with_text("Select Level", font)?;
+            Button {
+                center,
+                inner,
+                kind: ButtonKind::LevelSelect,
+            }
+        };
+
         let high_score = {
-            let center = glm::ivec2(640, 500);
+            let center = glm::ivec2(640, 420);
             let inner = button::Static::with_text("High Scores", font)?;
             Button {
                 center,
@@ -199,23 +227,59 @@ impl<T> ButtonManager<T> {
             }
         };
 
+        let key_bindings = {
+            let center = glm::ivec2(640, 500);
+            let inner = button::Static::with_text("Controls", font)?;
+            Button {
+                center,
+                inner,
+                kind: ButtonKind::KeyBindings,
+            }
+        };
+
+        let options = {
+            let center = glm::ivec2(640, 580);
+            let inner = button::Static::with_text("Options", font)?;
+            Button {
+                center,
+                inner,
+                kind: ButtonKind::Options,
+            }
+        };
+
         Ok(ButtonManager {
             new_game,
+            level_select,
             high_score,
+            key_bindings,
+            options,
             picker,
             selected: ButtonKind::NewGame,
         })
     }
 
-    pub fn update(&mut self, input: &input::State) -> Option<ButtonKind> {
-        if input.did_press_key(Keycode::Down) ^ input.did_press_key(Keycode::Up) {
+    pub fn update(&mut self, actions: &Snapshot) -> Option<ButtonKind> {
+        let down = actions.did_press(Action::MoveDown);
+        let up = actions.did_press(Action::Jump);
+        if down && !up {
+            self.selected = match self.selected {
+                ButtonKind::NewGame => ButtonKind::LevelSelect,
+                ButtonKind::LevelSelect => ButtonKind::HighScore,
+                ButtonKind::HighScore => ButtonKind::KeyBindings,
+                ButtonKind::KeyBindings => ButtonKind::Options,
+                ButtonKind::Options => ButtonKind::NewGame,
+            }
+        } else if up && !down {
             self.selected = match self.selected {
-                ButtonKind::NewGame => ButtonKind::HighScore,
-                ButtonKind::HighScore => ButtonKind::NewGame,
+                ButtonKind::NewGame => ButtonKind::Options,
+                ButtonKind::LevelSelect => ButtonKind::NewGame,
+                ButtonKind::HighScore => ButtonKind::LevelSelect,
+                ButtonKind::KeyBindings => ButtonKind::HighScore,
+                ButtonKind::Options => ButtonKind::KeyBindings,
             }
         }
 
-        if input.did_press_key(Keycode::Return) {
+        if actions.did_press(Action::Confirm) {
             Some(self.selected)
         } else {
             None
@@ -265,6 +329,9 @@ where
             picker: &*self.picker,
         };
         renderer.show(&self.new_game)?;
-        renderer.show(&self.high_score)
+        renderer.show(&self.level_select)?;
+        renderer.show(&self.high_score)?;
+        renderer.show(&self.key_bindings)?;
+        renderer.show(&self.options)
     }
 }
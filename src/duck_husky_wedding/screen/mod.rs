@@ -2,34 +2,77 @@ mod menu;
 mod finish;
 mod game_play;
 mod high_score;
+mod key_bindings;
+mod level_select;
+mod options;
+mod pause;
 mod player_select;
 
 use data;
+use duck_husky_wedding::audio::{Music, Sound};
+use duck_husky_wedding::controls::{self, Bindings, Snapshot};
+use duck_husky_wedding::font::Fonts;
+use duck_husky_wedding::reload::Changes;
+use duck_husky_wedding::replay::Recording;
+use duck_husky_wedding::settings::{self, Settings};
 use self::menu::Menu;
-use self::game_play::{GamePlay, PlayerKind};
 use self::high_score::HighScore;
+use self::key_bindings::KeyBindings;
+use self::level_select::LevelSelect;
+use self::options::Options;
 use self::player_select::PlayerSelect;
 
+use glm;
 use moho::{self, input};
-use moho::renderer::{Canvas, Font, FontLoader, FontManager, Scene, Texture, TextureLoader,
-                     TextureManager};
+use moho::renderer::{Canvas, ColorRGBA, Font, FontLoader, Scene, Texture,
+                     TextureLoader, TextureManager};
+use sdl2::rect::Rect;
 
 use errors::*;
 
 use std::rc::Rc;
 use std::time::Duration;
 
+pub use self::game_play::{Attempt, GamePlay, Outcome, PlayerKind};
+pub use self::game_play::Data as GamePlayData;
+
+#[derive(Clone, Copy)]
 pub enum Kind {
     Menu,
-    GamePlay(PlayerKind),
-    HighScore,
-    PlayerSelect,
+    GamePlay(Attempt),
+    HighScore(usize, PlayerKind),
+    KeyBindings,
+    LevelSelect,
+    Options,
+    PlayerSelect(usize),
+}
+
+static NAVIGATE: [Sound; 1] = [Sound::Navigate];
+
+// bordered box that the in-game overlays are drawn on
+fn show_frame<'t, R: Canvas<'t>>(renderer: &mut R, view: &glm::IVec4) -> moho::errors::Result<()> {
+    //border
+    renderer.set_draw_color(ColorRGBA(0, 0, 0, 255));
+    renderer.fill_rects(&[Rect::new(view.x, view.y, view.z as u32, view.w as u32)])?;
+    //background
+    renderer.set_draw_color(ColorRGBA(60, 0, 70, 255));
+    renderer.fill_rects(&[
+        Rect::new(
+            view.x + 6,
+            view.y + 6,
+            view.z as u32 - 12,
+            view.w as u32 - 12,
+        ),
+    ])
 }
 
 pub enum Screen<T, F> {
     Menu(Menu<T>),
     GamePlay(GamePlay<T, F>),
     HighScore(HighScore<T>),
+    KeyBindings(KeyBindings<T, F>),
+    LevelSelect(LevelSelect<T>),
+    Options(Options<T, F>),
     PlayerSelect(PlayerSelect<T>),
 }
 
@@ -42,26 +85,85 @@ where
             Screen::Menu(ref s) => renderer.show(s),
             Screen::GamePlay(ref s) => renderer.show(s),
             Screen::HighScore(ref s) => renderer.show(s),
+            Screen::KeyBindings(ref s) => renderer.show(s),
+            Screen::LevelSelect(ref s) => renderer.show(s),
+            Screen::Options(ref s) => renderer.show(s),
             Screen::PlayerSelect(ref s) => renderer.show(s),
         }
     }
 }
 
 impl<T: Texture, F: Font<Texture = T>> Screen<T, F> {
-    pub fn update(&mut self, delta: Duration, input: &input::State) -> Option<Kind> {
+    pub fn update(
+        &mut self,
+        delta: Duration,
+        input: &input::State,
+        actions: &Snapshot,
+    ) -> Option<Kind> {
         match *self {
-            Screen::Menu(ref mut s) => s.update(input),
-            Screen::GamePlay(ref mut s) => s.update(delta, input),
-            Screen::HighScore(ref mut s) => s.update(input),
-            Screen::PlayerSelect(ref mut s) => s.update(delta, input),
+            Screen::Menu(ref mut s) => s.update(actions),
+            Screen::GamePlay(ref mut s) => s.update(delta, actions),
+            Screen::HighScore(ref mut s) => s.update(actions),
+            Screen::KeyBindings(ref mut s) => s.update(input),
+            Screen::LevelSelect(ref mut s) => s.update(actions),
+            Screen::Options(ref mut s) => s.update(actions),
+            Screen::PlayerSelect(ref mut s) => s.update(delta, input, actions),
         }
     }
 
-    pub fn before_draw(&mut self) -> Result<()> {
+    // whether the player is being moved around, as opposed to picking from a menu
+    pub fn is_playing(&self) -> bool {
+        match *self {
+            Screen::GamePlay(ref s) => s.is_running(),
+            _ => false,
+        }
+    }
+
+    // only the game play moves smoothly enough to draw in between updates
+    pub fn interpolate(&mut self, alpha: f64) {
         if let Screen::GamePlay(ref mut s) = *self {
-            s.before_draw()
+            s.interpolate(alpha);
+        }
+    }
+
+    // what can be heard from the last update
+    pub fn sounds(&self) -> &[Sound] {
+        let navigated = match *self {
+            Screen::GamePlay(ref s) => return s.sounds(),
+            Screen::Menu(ref s) => s.navigated,
+            Screen::LevelSelect(ref s) => s.navigated,
+            Screen::Options(ref s) => s.navigated,
+            Screen::PlayerSelect(ref s) => s.navigated,
+            Screen::HighScore(_) | Screen::KeyBindings(_) => false,
+        };
+        if navigated {
+            &NAVIGATE
         } else {
-            Ok(())
+            &[]
+        }
+    }
+
+    // what the options screen changed during the last update
+    pub fn settings(&self) -> Option<&Settings> {
+        match *self {
+            Screen::Options(ref s) if s.changed => Some(s.settings()),
+            _ => None,
+        }
+    }
+
+    pub fn music(&self) -> Music {
+        match *self {
+            Screen::GamePlay(ref s) => s.music(),
+            _ => Music::Menu,
+        }
+    }
+
+    pub fn before_draw(&mut self) -> Result<()> {
+        match *self {
+            Screen::GamePlay(ref mut s) => s.before_draw(),
+            Screen::KeyBindings(ref mut s) => s.before_draw(),
+            Screen::Options(ref mut s) => s.before_draw(),
+            _ => Ok(()),
         }
     }
 }
@@ -70,16 +172,21 @@ pub struct Manager<T, F> {
     menu: Menu<T>,
     game_play: game_play::Data<T>,
     high_score: high_score::Data<T>,
+    key_bindings: key_bindings::Data<T>,
+    level_select: level_select::Data<T>,
+    options: options::Data<T>,
     player_select: player_select::Data<T>,
+    bindings: Bindings,
+    settings: Settings,
     //kind of current screen
     active: Screen<T, F>,
 }
 
 impl<T, F: Font<Texture = T>> Manager<T, F> {
     pub fn load<'f, 't, TL, FL>(
-        font_manager: &mut FontManager<'f, FL>,
+        font_manager: &mut Fonts<'f, FL>,
         texture_manager: &mut TextureManager<'t, TL>,
-        level: &data::Level,
+        campaign: data::Campaign,
         game: data::Game,
     ) -> Result<Self>
     where
@@ -90,19 +197,41 @@ impl<T, F: Font<Texture = T>> Manager<T, F> {
         let picker = game.heart.texture.load(texture_manager)?;
         let player_select =
             player_select::Data::load(font_manager, texture_manager, &game, Rc::clone(&picker))?;
-        let menu = Menu::load(font_manager, texture_manager, &game, picker)?;
+        let menu = Menu::load(font_manager, texture_manager, &game, Rc::clone(&picker))?;
         let active = Screen::Menu(menu.clone());
-        let game_play = game_play::Data::load(texture_manager, level, game)?;
-        let high_score = high_score::Data::load(font_manager)?;
+        let campaign = Rc::new(campaign);
+        let key_bindings = key_bindings::Data::load(font_manager, Rc::clone(&picker))?;
+        let options = options::Data::load(font_manager, Rc::clone(&picker))?;
+        let level_select = level_select::Data::load(font_manager, Rc::clone(&campaign), picker)?;
+        let high_score = high_score::Data::load(font_manager, Rc::clone(&campaign))?;
+        let game_play = game_play::Data::load(texture_manager, campaign, Rc::new(game))?;
         Ok(Manager {
             menu: menu,
             game_play: game_play,
             high_score: high_score,
+            key_bindings: key_bindings,
+            level_select: level_select,
+            options: options,
             player_select: player_select,
+            bindings: controls::get(),
+            settings: settings::get(),
             active: active,
         })
     }
 
+    // `pad` holds whatever game controllers are plugged in
+    pub fn update(&mut self, delta: Duration, input: &input::State, pad: Snapshot) -> Option<Kind>
+    where
+        T: Texture,
+    {
+        let actions = Snapshot::capture(input, &self.bindings).combine(pad);
+        self.active.update(delta, input, &actions)
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
     pub fn mut_screen(&mut self) -> &mut Screen<T, F> {
         &mut self.active
     }
@@ -111,25 +240,94 @@ impl<T, F: Font<Texture = T>> Manager<T, F> {
         &self.active
     }
 
+    pub fn replay<'f, 't, FL, TL>(
+        &mut self,
+        recording: Recording,
+        font_manager: &mut Fonts<'f, FL>,
+        texture_manager: &mut TextureManager<'t, TL>,
+    ) -> Result<()>
+    where
+        T: Texture,
+        FL: FontLoader<'f, Font = F>,
+        TL: TextureLoader<'t, Texture = T>,
+    {
+        let game_play = self.game_play
+            .replay(texture_manager, font_manager, recording)?;
+        self.active = Screen::GamePlay(game_play);
+        Ok(())
+    }
+
+    // brings the game play up to date with what changed on disk; the game data is read
+    // again only when it or a sprite changed, and nothing changes when any of it fails to
+    // load
+    pub fn reload<'t, TL>(
+        &mut self,
+        texture_manager: &mut TextureManager<'t, TL>,
+        changes: &Changes,
+    ) -> Result<()>
+    where
+        T: Texture,
+        TL: TextureLoader<'t, Texture = T>,
+    {
+        let fresh = if changes.game || changes.sprites {
+            let game = data::Game::load(data::media("game_data.yaml"))
+                .chain_err(|| "cannot load the game data")?;
+            Some(self.game_play.with_game(texture_manager, game)?)
+        } else {
+            None
+        };
+        if let Screen::GamePlay(ref mut s) = self.active {
+            fresh
+                .as_ref()
+                .unwrap_or(&self.game_play)
+                .reload(texture_manager, s, changes)?;
+        }
+        if let Some(game_play) = fresh {
+            self.game_play = game_play;
+        }
+        Ok(())
+    }
+
+    // a level that cannot be started leaves the level select up and returns why
     pub fn select_screen<'f, 't, FL, TL>(
         &mut self,
         screen: Kind,
-        font_manager: &mut FontManager<'f, FL>,
+        font_manager: &mut Fonts<'f, FL>,
         texture_manager: &mut TextureManager<'t, TL>,
-    ) where
+    ) -> Result<()>
+    where
         T: Texture,
         FL: FontLoader<'f, Font = F>,
         TL: TextureLoader<'t, Texture = T>,
     {
+        match self.active {
+            Screen::KeyBindings(ref s) => self.bindings = s.bindings().clone(),
+            Screen::Options(ref s) => self.settings = *s.settings(),
+            _ => {}
+        }
         self.active = match screen {
             Kind::Menu => Screen::Menu(self.menu.clone()),
-            Kind::PlayerSelect => Screen::PlayerSelect(self.player_select.activate()),
-            Kind::GamePlay(k) => Screen::GamePlay(
-                self.game_play
-                    .activate(texture_manager, font_manager, k)
-                    .unwrap(),
-            ),
-            Kind::HighScore => Screen::HighScore(self.high_score.activate(font_manager).unwrap()),
-        }
+            Kind::PlayerSelect(l) => Screen::PlayerSelect(self.player_select.activate(l)),
+            Kind::GamePlay(a) => {
+                let started = self.game_play.activate(texture_manager, font_manager, a);
+                match started {
+                    Ok(g) => Screen::GamePlay(g),
+                    Err(e) => {
+                        let levels = self.level_select.activate(font_manager)?;
+                        self.active = Screen::LevelSelect(levels);
+                        return Err(e);
+                    }
+                }
+            }
+            Kind::HighScore(l, p) => {
+                Screen::HighScore(self.high_score.activate(font_manager, l, p)?)
+            }
+            Kind::KeyBindings => {
+                Screen::KeyBindings(self.key_bindings.activate(font_manager, &self.bindings)?)
+            }
+            Kind::LevelSelect => Screen::LevelSelect(self.level_select.activate(font_manager)?),
+            Kind::Options => Screen::Options(self.options.activate(font_manager, &self.settings)?),
+        };
+        Ok(())
     }
 }
@@ -0,0 +1,258 @@
+use duck_husky_wedding::controls::{Action, Snapshot};
+use duck_husky_wedding::font;
+use duck_husky_wedding::settings::{self, Settings};
+use utils::Try;
+use errors::*;
+
+use moho;
+use moho::renderer::{align, options, ColorRGBA, Font, Renderer, Scene, Texture};
+
+use std::cmp;
+use std::rc::Rc;
+
+const VOLUME_STEP: u8 = 10;
+
+#[derive(Clone, Copy)]
+enum Field {
+    Display,
+    Window,
+    VSync,
+    MusicVolume,
+    EffectsVolume,
+    Language,
+}
+
+static FIELDS: [Field; 6] = [
+    Field::Display,
+    Field::Window,
+    Field::VSync,
+    Field::MusicVolume,
+    Field::EffectsVolume,
+    Field::Language,
+];
+
+impl Field {
+    fn label(&self) -> &'static str {
+        match *self {
+            Field::Display => "Display",
+            Field::Window => "Window Size",
+            Field::VSync => "VSync (on restart)",
+            Field::MusicVolume => "Music Volume",
+            Field::EffectsVolume => "Effects Volume",
+            Field::Language => "Language",
+        }
+    }
+
+    fn value(&self, settings: &Settings) -> String {
+        match *self {
+            Field::Display => settings.display.label().to_string(),
+            Field::Window => format!("{}x{}", settings.window.x, settings.window.y),
+            Field::VSync => String::from(if settings.vsync { "On" } else { "Off" }),
+            Field::MusicVolume => format!("{}%", settings.music_volume),
+            Field::EffectsVolume => format!("{}%", settings.effects_volume),
+            Field::Language => settings.language.label().to_string(),
+        }
+    }
+
+    fn change(&self, settings: &mut Settings, forward: bool) {
+        match *self {
+            Field::Display => {
+                settings.display = cycle(&settings::DISPLAY_MODES, settings.display, forward)
+            }
+            Field::Window => {
+                settings.window = cycle(&settings::WINDOW_SIZES, settings.window, forward)
+            }
+            Field::VSync => settings.vsync = !settings.vsync,
+            Field::MusicVolume => settings.music_volume = step(settings.music_volume, forward),
+            Field::EffectsVolume => {
+                settings.effects_volume = step(settings.effects_volume, forward)
+            }
+            Field::Language => {
+                settings.language = cycle(&settings::LANGUAGES, settings.language, forward)
+            }
+        }
+    }
+}
+
+// the value next to `current`; one that is not offered goes back to the first
+fn cycle<V: Copy + PartialEq>(all: &[V], current: V, forward: bool) -> V {
+    let count = all.len();
+    match all.iter().position(|&v| v == current) {
+        Some(i) if forward => all[(i + 1) % count],
+        Some(i) => all[(i + count - 1) % count],
+        None => all[0],
+    }
+}
+
+fn step(volume: u8, forward: bool) -> u8 {
+    if forward {
+        cmp::min(volume.saturating_add(VOLUME_STEP), 100)
+    } else {
+        volume.saturating_sub(VOLUME_STEP)
+    }
+}
+
+struct Row<T> {
+    label: T,
+    value: T,
+}
+
+pub struct Options<T, F> {
+    title: Rc<T>,
+    instructions: Rc<T>,
+    picker: Rc<T>,
+    font: Rc<F>,
+    rows: Vec<Row<T>>,
+    settings: Settings,
+    selected: usize,
+    // whether the values need drawing again
+    stale: bool,
+    // whether the last update changed anything
+    pub changed: bool,
+    pub navigated: bool,
+}
+
+pub struct Data<T> {
+    title: Rc<T>,
+    instructions: Rc<T>,
+    picker: Rc<T>,
+}
+
+impl<T> Data<T> {
+    pub fn load<FM>(font_manager: &mut FM, picker: Rc<T>) -> Result<Self>
+    where
+        FM: font::Manager,
+        FM::Font: Font<Texture = T>,
+    {
+        let color = ColorRGBA(255, 255, 0, 255);
+
+        let title = {
+            let font = font_manager.load(font::Kind::KenPixel, 64)?;
+            font.texturize("Options", &color).map(Rc::new)
+        }?;
+        let instructions = {
+            let text = "<Up and Down to choose; Left and Right to change; Escape to go back>";
+            let font = font_manager.load(font::Kind::KenPixel, 32)?;
+            font.texturize(text, &color).map(Rc::new)
+        }?;
+
+        Ok(Data {
+            title,
+            instructions,
+            picker,
+        })
+    }
+
+    pub fn activate<FM>(
+        &self,
+        font_manager: &mut FM,
+        settings: &Settings,
+    ) -> Result<Options<T, FM::Font>>
+    where
+        FM: font::Manager,
+        FM::Font: Font<Texture = T>,
+    {
+        let font = font_manager.load(font::Kind::KenPixel, 40)?;
+        let color = ColorRGBA(255, 255, 255, 255);
+        let rows = FIELDS
+            .iter()
+            .map(|f| -> Result<Row<T>> {
+                let label = font.texturize(f.label(), &color)?;
+                let value = font.texturize(&f.value(settings), &color)?;
+                Ok(Row { label, value })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Options {
+            rows,
+            font,
+            settings: *settings,
+            selected: 0,
+            stale: false,
+            changed: false,
+            navigated: false,
+            title: Rc::clone(&self.title),
+            instructions: Rc::clone(&self.instructions),
+            picker: Rc::clone(&self.picker),
+        })
+    }
+}
+
+impl<T, F: Font<Texture = T>> Options<T, F> {
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    // every change is saved right away
+    pub fn update(&mut self, actions: &Snapshot) -> Option<super::Kind> {
+        let down = actions.did_press(Action::MoveDown);
+        let up = actions.did_press(Action::Jump);
+        let left = actions.did_press(Action::MoveLeft);
+        let right = actions.did_press(Action::MoveRight);
+        let count = self.rows.len();
+        let selected = self.selected;
+
+        if down && !up {
+            self.selected = (self.selected + 1) % count;
+        } else if up && !down {
+            self.selected = (self.selected + count - 1) % count;
+        }
+
+        self.changed = left != right;
+        if self.changed {
+            FIELDS[self.selected].change(&mut self.settings, right);
+            if let Err(e) = settings::create(&self.settings) {
+                println!("could not save settings: {:?}", e);
+            }
+            self.stale = true;
+        }
+        self.navigated = self.changed || selected != self.selected;
+
+        if actions.did_press(Action::Back) {
+            Some(super::Kind::Menu)
+        } else {
+            None
+        }
+    }
+
+    pub fn before_draw(&mut self) -> Result<()> {
+        if self.stale {
+            let color = ColorRGBA(255, 255, 255, 255);
+            for (f, r) in FIELDS.iter().zip(&mut self.rows) {
+                r.value = self.font.texturize(&f.value(&self.settings), &color)?;
+            }
+            self.stale = false;
+        }
+        Ok(())
+    }
+}
+
+impl<'t, R: Renderer<'t>, F> Scene<R> for Options<R::Texture, F>
+where
+    R::Texture: Texture,
+{
+    fn show(&self, renderer: &mut R) -> moho::errors::Result<()> {
+        let center = align::center(640);
+
+        renderer.copy(&*self.title, options::at(center.top(0)))?;
+
+        {
+            let texture = &*self.instructions;
+            let dst = center.bottom(720 - texture.dims().y as i32);
+            renderer.copy(texture, options::at(dst))
+        }?;
+
+        self.rows
+            .iter()
+            .enumerate()
+            .map(|(i, r)| -> moho::errors::Result<()> {
+                let middle = align::middle(160 + 75 * i as i32);
+                if i == self.selected {
+                    renderer.copy(&*self.picker, options::at(middle.right(290)))?;
+                }
+                renderer.copy(&r.label, options::at(middle.left(300)))?;
+                renderer.copy(&r.value, options::at(middle.left(800)))
+            })
+            .try()
+    }
+}
@@ -0,0 +1,114 @@
+use duck_husky_wedding::button;
+use duck_husky_wedding::controls::{Action, Snapshot};
+use errors::*;
+
+use glm;
+use moho;
+use moho::renderer::{align, options, Canvas, ColorRGBA, Font, Scene, Texture};
+
+use std::rc::Rc;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Choice {
+    Resume,
+    Restart,
+    Quit,
+}
+
+static CHOICES: [Choice; 3] = [Choice::Resume, Choice::Restart, Choice::Quit];
+
+pub struct Pause<T> {
+    view: glm::IVec4,
+    title: Rc<T>,
+    buttons: Vec<button::Static<T>>,
+    selected: usize,
+    pub navigated: bool,
+}
+
+impl<T> Clone for Pause<T> {
+    fn clone(&self) -> Self {
+        Pause {
+            view: self.view,
+            title: Rc::clone(&self.title),
+            buttons: self.buttons.clone(),
+            selected: 0,
+            navigated: false,
+        }
+    }
+}
+
+impl<T> Pause<T> {
+    pub fn load<F>(title_font: &F, font: &F) -> Result<Self>
+    where
+        F: Font<Texture = T>,
+    {
+        let title = title_font
+            .texturize("PAUSED", &ColorRGBA(255, 255, 0, 255))
+            .map(Rc::new)?;
+        let buttons = ["Resume", "Restart Level", "Quit to Menu"]
+            .iter()
+            .map(|t| button::Static::with_text(t, font))
+            .collect::<Result<Vec<_>>>()?;
+        let x_size = 600;
+        let y_size = 340;
+        Ok(Pause {
+            title,
+            buttons,
+            view: glm::ivec4(640 - x_size / 2, 360 - y_size / 2, x_size, y_size),
+            selected: 0,
+            navigated: false,
+        })
+    }
+
+    pub fn update(&mut self, actions: &Snapshot) -> Option<Choice> {
+        let down = actions.did_press(Action::MoveDown);
+        let up = actions.did_press(Action::Jump);
+        let count = self.buttons.len();
+        let selected = self.selected;
+
+        if down && !up {
+            self.selected = (self.selected + 1) % count;
+        } else if up && !down {
+            self.selected = (self.selected + count - 1) % count;
+        }
+
+        let choice = if actions.did_press(Action::Pause) {
+            Some(Choice::Resume)
+        } else if actions.did_press(Action::Confirm) {
+            Some(CHOICES[self.selected])
+        } else {
+            None
+        };
+        self.navigated = choice.is_some() || selected != self.selected;
+        choice
+    }
+}
+
+impl<'t, R: Canvas<'t>> Scene<R> for Pause<R::Texture>
+where
+    R::Texture: Texture,
+{
+    fn show(&self, renderer: &mut R) -> moho::errors::Result<()> {
+        super::show_frame(renderer, &self.view)?;
+
+        let mut top = align::top(self.view.y + 10);
+        {
+            let texture = &*self.title;
+            let options = options::at(top.center(640));
+            top = top.nudge(20 + texture.dims().y as i32);
+            renderer.copy(texture, options)
+        }?;
+
+        for (i, b) in self.buttons.iter().enumerate() {
+            let texture = if i == self.selected {
+                &*b.selected
+            } else {
+                &*b.idle
+            };
+            let options = options::at(top.center(640));
+            top = top.nudge(10 + b.dims.y as i32);
+            renderer.copy(texture, options)?;
+        }
+        Ok(())
+    }
+}
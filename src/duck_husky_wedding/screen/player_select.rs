@@ -1,5 +1,6 @@
 use data;
 use duck_husky_wedding::button;
+use duck_husky_wedding::controls::{Action, Snapshot};
 use duck_husky_wedding::font;
 use errors::*;
 
@@ -54,6 +55,11 @@ pub struct PlayerSelect<T> {
     gem: Animated<T>,
     coin: Animated<T>,
     cat: Animated<T>,
+    ghost_on: Rc<T>,
+    ghost_off: Rc<T>,
+    ghost: bool,
+    level: usize,
+    pub navigated: bool,
 }
 
 pub struct Data<T> {
@@ -65,6 +71,8 @@ pub struct Data<T> {
     gem: AnimatedData<T>,
     coin: AnimatedData<T>,
     cat: AnimatedData<T>,
+    ghost_on: Rc<T>,
+    ghost_off: Rc<T>,
 }
 
 impl<T> Data<T> {
@@ -119,6 +127,13 @@ impl<T> Data<T> {
                 &title_color,
             ).map(Rc::new)
         }?;
+        let (ghost_on, ghost_off) = {
+            let font = font_manager.load(font::Kind::KenPixel, 32)?;
+            let color = ColorRGBA(255, 255, 255, 255);
+            let on = font.texturize("Ghost racer: ON (G to toggle)", &color)?;
+            let off = font.texturize("Ghost racer: OFF (G to toggle)", &color)?;
+            (Rc::new(on), Rc::new(off))
+        };
 
         Ok(Data {
             title,
@@ -129,11 +144,14 @@ impl<T> Data<T> {
             gem,
             cat,
             instructions,
+            ghost_on,
+            ghost_off,
         })
     }
 
-    pub fn activate(&self) -> PlayerSelect<T> {
+    pub fn activate(&self, level: usize) -> PlayerSelect<T> {
         PlayerSelect {
+            level,
             title: Rc::clone(&self.title),
             button_manager: self.button_manager.clone(),
             collect_text: Rc::clone(&self.collect_text),
@@ -142,19 +160,40 @@ impl<T> Data<T> {
             coin: self.coin.start(),
             cat: self.cat.start(),
             instructions: Rc::clone(&self.instructions),
+            ghost_on: Rc::clone(&self.ghost_on),
+            ghost_off: Rc::clone(&self.ghost_off),
+            ghost: false,
+            navigated: false,
         }
     }
 }
 
 impl<T> PlayerSelect<T> {
-    pub fn update(&mut self, delta: Duration, input: &input::State) -> Option<super::Kind> {
-        let next = self.button_manager.update(delta, input);
+    pub fn update(
+        &mut self,
+        delta: Duration,
+        input: &input::State,
+        actions: &Snapshot,
+    ) -> Option<super::Kind> {
+        let selected = self.button_manager.kind();
+        let next = self.button_manager.update(delta, actions);
+        self.navigated = next.is_some() || selected != self.button_manager.kind();
         if next.is_none() {
             self.gem.animate(delta);
             self.coin.animate(delta);
             self.cat.animate(delta);
         }
-        next.map(super::Kind::GamePlay)
+        if input.did_press_key(Keycode::G) {
+            self.ghost = !self.ghost;
+        }
+        let (level, ghost) = (self.level, self.ghost);
+        next.map(|player| {
+            super::Kind::GamePlay(super::Attempt {
+                player,
+                level,
+                ghost,
+            })
+        })
     }
 }
 
@@ -170,6 +209,14 @@ where
         renderer.copy(&self.collect_text, options::at(align::top(400).center(320)))?;
         renderer.show(&self.coin)?;
         renderer.show(&self.gem)?;
+        {
+            let texture = if self.ghost {
+                &*self.ghost_on
+            } else {
+                &*self.ghost_off
+            };
+            renderer.copy(texture, options::at(align::top(340).center(640)))
+        }?;
         renderer.copy(
             &self.instructions,
             options::at(align::bottom(720 - self.instructions.dims().y as i32).center(640)),
@@ -265,13 +312,13 @@ impl<T> ButtonManager<T> {
         })
     }
 
-    fn update(&mut self, elapsed: Duration, input: &input::State) -> Option<super::PlayerKind> {
+    fn update(&mut self, elapsed: Duration, actions: &Snapshot) -> Option<super::PlayerKind> {
         if let Some(ref mut s) = self.selected {
             s.animation.animate(elapsed);
         }
 
-        let left = input.did_press_key(Keycode::Left);
-        let right = input.did_press_key(Keycode::Right);
+        let left = actions.did_press(Action::MoveLeft);
+        let right = actions.did_press(Action::MoveRight);
 
         if left && !right {
             match self.selected {
@@ -303,12 +350,16 @@ impl<T> ButtonManager<T> {
             }
         }
 
-        if input.did_press_key(Keycode::Return) {
-            self.selected.as_ref().map(|s| s.kind)
+        if actions.did_press(Action::Confirm) {
+            self.kind()
         } else {
             None
         }
     }
+
+    fn kind(&self) -> Option<super::PlayerKind> {
+        self.selected.as_ref().map(|s| s.kind)
+    }
 }
 
 struct Button<T> {
@@ -0,0 +1,147 @@
+use data::{self, Dimension};
+use errors::*;
+
+use sdl2::render::Canvas;
+use sdl2::video::{self, FullscreenType};
+use serde_yaml;
+
+const FILE: &'static str = "settings.yaml";
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub enum DisplayMode {
+    Windowed,
+    // changes the resolution of the display to the window size
+    Fullscreen,
+    // covers the display at whatever resolution it already has
+    Borderless,
+}
+
+pub static DISPLAY_MODES: [DisplayMode; 3] = [
+    DisplayMode::Windowed,
+    DisplayMode::Fullscreen,
+    DisplayMode::Borderless,
+];
+
+impl DisplayMode {
+    pub fn label(&self) -> &'static str {
+        match *self {
+            DisplayMode::Windowed => "Windowed",
+            DisplayMode::Fullscreen => "Fullscreen",
+            DisplayMode::Borderless => "Borderless",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub enum Language {
+    English,
+}
+
+// only english has any text so far
+pub static LANGUAGES: [Language; 1] = [Language::English];
+
+impl Language {
+    pub fn label(&self) -> &'static str {
+        match *self {
+            Language::English => "English",
+        }
+    }
+}
+
+// offered by the options screen; any other size can still be set in the file
+pub static WINDOW_SIZES: [Dimension; 4] = [
+    Dimension { x: 1280, y: 720 },
+    Dimension { x: 1600, y: 900 },
+    Dimension { x: 1920, y: 1080 },
+    Dimension { x: 2560, y: 1440 },
+];
+
+fn default_display() -> DisplayMode {
+    DisplayMode::Borderless
+}
+
+fn default_window() -> Dimension {
+    WINDOW_SIZES[0]
+}
+
+fn default_vsync() -> bool {
+    true
+}
+
+fn full_volume() -> u8 {
+    100
+}
+
+fn default_language() -> Language {
+    Language::English
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub struct Settings {
+    #[serde(default = "default_display")] pub display: DisplayMode,
+    // the game is drawn at 1280x720 and scaled to fit whatever size this is
+    #[serde(default = "default_window")] pub window: Dimension,
+    // only takes effect the next time the game starts
+    #[serde(default = "default_vsync")] pub vsync: bool,
+    // from 0 to 100, scaling the mix set in the game data
+    #[serde(default = "full_volume")] pub music_volume: u8,
+    #[serde(default = "full_volume")] pub effects_volume: u8,
+    #[serde(default = "default_language")] pub language: Language,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            display: default_display(),
+            window: default_window(),
+            vsync: default_vsync(),
+            music_volume: full_volume(),
+            effects_volume: full_volume(),
+            language: default_language(),
+        }
+    }
+}
+
+pub fn get() -> Settings {
+    let file = match data::open_user(FILE) {
+        Ok(f) => f,
+        // nothing was saved yet
+        Err(_) => return Settings::default(),
+    };
+    serde_yaml::from_reader(file).unwrap_or_else(|e| {
+        println!("could not read settings file, using the defaults: {:?}", e);
+        Settings::default()
+    })
+}
+
+pub fn create(settings: &Settings) -> Result<()> {
+    let file = data::create_user(FILE)?;
+    serde_yaml::to_writer(file, settings).map_err(Into::into)
+}
+
+// whatever the game is drawn on that can be changed while it runs
+pub trait Display {
+    fn apply(&mut self, settings: &Settings) -> Result<()>;
+}
+
+impl Display for Canvas<video::Window> {
+    fn apply(&mut self, settings: &Settings) -> Result<()> {
+        let window = self.window_mut();
+        let fullscreen = match settings.display {
+            DisplayMode::Windowed => FullscreenType::Off,
+            DisplayMode::Fullscreen => FullscreenType::True,
+            DisplayMode::Borderless => FullscreenType::Desktop,
+        };
+        // leave fullscreen first so the new size applies to the window itself
+        if fullscreen != FullscreenType::True {
+            window.set_fullscreen(fullscreen)?;
+        }
+        window
+            .set_size(settings.window.x, settings.window.y)
+            .chain_err(|| "cannot resize the window")?;
+        if fullscreen == FullscreenType::True {
+            window.set_fullscreen(fullscreen)?;
+        }
+        Ok(())
+    }
+}
@@ -1,18 +1,24 @@
 use data;
 use errors::*;
 use duck_husky_wedding::background::Background;
+use duck_husky_wedding::body::Body;
+use duck_husky_wedding::camera::ViewPort;
 use duck_husky_wedding::cat::{self, Cat};
+use duck_husky_wedding::checkpoint::Checkpoint;
 use duck_husky_wedding::collectable::{self, Collectable};
 use duck_husky_wedding::goal::Goal;
 use duck_husky_wedding::npc::Npc;
-use duck_husky_wedding::player::Player;
+use duck_husky_wedding::player::{Player, FLOOR};
 use duck_husky_wedding::obstacle::Obstacle;
+use duck_husky_wedding::platform::Platform;
 use utils::Try;
 
 use glm;
 use moho;
 use moho::shape::Rectangle;
-use moho::renderer::{options, Renderer, Scene, Texture, TextureLoader, TextureManager};
+use moho::renderer::{options, ColorRGBA, Renderer, Scene, Texture, TextureLoader,
+                     TextureManager};
+use sdl2::rect::Rect;
 
 use std::rc::Rc;
 use std::time::Duration;
@@ -96,6 +102,11 @@ impl<T> Clone for Spike<T> {
 }
 
 pub struct Data<T> {
+    // in pixels
+    pub size: glm::IVec2,
+    // bottom left corner of where the player drops in, if the level says
+    pub spawn: Option<glm::IVec2>,
+    pub time_limit: Duration,
     background: Background<T>,
     obstacles: Vec<Obstacle<T>>,
     spikes: Vec<Spike<T>>,
@@ -103,12 +114,21 @@ pub struct Data<T> {
     npc_pos: glm::UVec2,
     collectables: Vec<collectable::Data<T>>,
     enemies: Vec<cat::Data<T>>,
+    checkpoints: Vec<Checkpoint<T>>,
+    pits: Vec<glm::IVec4>,
+    platforms: Vec<Platform<T>>,
 }
 
 pub struct World<T> {
+    pub size: glm::IVec2,
+    // what the camera sees, which the background layers scroll along with
+    view: glm::IVec4,
     background: Background<T>,
     obstacles: Vec<Obstacle<T>>,
     goal: Goal<T>,
+    pits: Vec<glm::IVec4>,
+    platforms: Vec<Platform<T>>,
+    pub checkpoints: Vec<Checkpoint<T>>,
     pub spikes: Vec<Spike<T>>,
     pub collectables: Vec<Collectable<T>>,
     pub npc: Npc<T>,
@@ -126,25 +146,48 @@ impl<T> Data<T> {
         TL: TextureLoader<'t, Texture = T>,
     {
         let tile_size: glm::IVec2 = game.ground.out_size.into();
-        let background = Background::load(texture_manager, &game.background)?;
+        let size = level.size(game.ground.out_size);
+        let bottom = size.y;
+        let background = {
+            let layers = if level.backgrounds.is_empty() {
+                &game.backgrounds
+            } else {
+                &level.backgrounds
+            };
+            Background::load(texture_manager, layers, size)
+        }?;
         let obstacles: Vec<_> = level
             .obstacles
             .iter()
-            .map(|o| Obstacle::load(texture_manager, &game.ground, o))
+            .map(|o| Obstacle::load(texture_manager, &game.ground, o, bottom))
+            .collect::<Result<_>>()?;
+        let platforms = level
+            .platforms
+            .iter()
+            .map(|p| Platform::load(texture_manager, &game.ground, p, bottom))
             .collect::<Result<_>>()?;
         let goal = {
-            let bl: glm::IVec2 = level.goal.into();
-            Goal::load(bl * tile_size, &game.goal, texture_manager)
+            let mut bl: glm::IVec2 = level.goal.into();
+            bl = bl * tile_size;
+            bl.y = bottom - bl.y;
+            Goal::load(bl, &game.goal, texture_manager)
         }?;
-        let npc_pos = glm::uvec2(goal.dst.x as u32, 720 - game.ground.out_size.y);
+        let npc_pos = glm::uvec2(goal.dst.x as u32, bottom as u32 - game.ground.out_size.y);
+        let spawn = level.spawn.map(|s| {
+            let mut bl: glm::IVec2 = s.into();
+            bl = bl * tile_size;
+            bl.y = bottom - bl.y;
+            bl
+        });
         let mut collectables = level
             .coins
             .iter()
             .map(|c| {
                 let mut bl: glm::IVec2 = (*c).into();
                 bl = bl * tile_size;
-                bl.y = 720 - bl.y;
-                collectable::Data::load(bl, &game.coin, texture_manager)
+                bl.y = bottom - bl.y;
+                let kind = collectable::Kind::Coin;
+                collectable::Data::load(bl, kind, &game.coin, texture_manager)
             })
             .collect::<Result<Vec<_>>>()?;
 
@@ -154,28 +197,38 @@ impl<T> Data<T> {
             .map(|g| {
                 let mut bl: glm::IVec2 = (*g).into();
                 bl = bl * tile_size;
-                bl.y = 720 - bl.y;
-                collectable::Data::load(bl, &game.gem, texture_manager)
+                bl.y = bottom - bl.y;
+                let kind = collectable::Kind::Gem;
+                collectable::Data::load(bl, kind, &game.gem, texture_manager)
             })
             .collect::<Result<Vec<_>>>()?;
 
         collectables.append(&mut gems);
+        let mut power_ups = level
+            .power_ups
+            .iter()
+            .map(|p| {
+                let power_up = match game.power_ups.iter().find(|u| u.kind == p.kind) {
+                    Some(u) => u,
+                    None => bail!("there is no {:?} power up in the game data", p.kind),
+                };
+                let mut bl: glm::IVec2 = p.bottom_left.into();
+                bl = bl * tile_size;
+                bl.y = bottom - bl.y;
+                let kind = collectable::Kind::PowerUp(p.kind);
+                collectable::Data::load(bl, kind, &power_up.pickup, texture_manager)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        collectables.append(&mut power_ups);
         let enemies = level
             .cats
             .iter()
             .map(|c| {
                 let mut bl: glm::IVec2 = c.bottom_left.into();
                 bl = bl * tile_size;
-                bl.y = 720 - bl.y;
-                let kind = match c.kind {
-                    data::CatKind::Idle => cat::Kind::Idle,
-                    data::CatKind::Moving(t) => cat::Kind::Moving {
-                        total: t * tile_size.x as u32,
-                        left: false,
-                        current: 0,
-                    },
-                };
-                cat::Data::load(bl, kind, &game.cat, texture_manager)
+                bl.y = bottom - bl.y;
+                cat::Data::load(bl, tile_size, c, &game.cat, texture_manager)
             })
             .collect::<Result<Vec<_>>>()?;
 
@@ -186,20 +239,24 @@ impl<T> Data<T> {
             level
                 .spikes
                 .iter()
-                .map(|s| {
+                .map(|s| -> Result<Spike<T>> {
                     let mut bl: glm::IVec2 = s.bottom_left.into();
                     bl = bl * tile_size;
-                    bl.y = 720 - bl.y;
+                    bl.y = bottom - bl.y;
                     let texture = Rc::clone(&texture);
                     let top_left = glm::ivec2(bl.x, bl.y - dims.y as i32);
-                    let expand_left = s.left
-                        .map(|l| l.load(&game.ground, texture_manager).unwrap());
-                    let expand_right = s.right
-                        .map(|l| l.load(&game.ground, texture_manager).unwrap());
-                    let expand_bottom = s.bottom
-                        .map(|l| l.load(&game.ground, texture_manager).unwrap());
-
-                    Spike {
+                    let at = s.bottom_left;
+                    let mut edge = |kind: Option<data::GroundKind>| match kind {
+                        None => Ok(None),
+                        Some(k) => k.load(&game.ground, texture_manager)
+                            .map(Some)
+                            .chain_err(|| format!("cannot load edge of spike at {:?}", at)),
+                    };
+                    let expand_left = edge(s.left)?;
+                    let expand_right = edge(s.right)?;
+                    let expand_bottom = edge(s.bottom)?;
+
+                    Ok(Spike {
                         count: s.count,
                         dims,
                         texture,
@@ -211,12 +268,36 @@ impl<T> Data<T> {
                             top_left: glm::dvec2(top_left.x.into(), (top_left.y + 9).into()),
                             dims: glm::dvec2((dims.x * s.count).into(), (dims.y - 9).into()),
                         },
-                    }
+                    })
                 })
-                .collect()
+                .collect::<Result<Vec<_>>>()?
         };
 
+        let checkpoints = level
+            .checkpoints
+            .iter()
+            .map(|c| {
+                let mut bl: glm::IVec2 = (*c).into();
+                bl = bl * tile_size;
+                bl.y = bottom - bl.y;
+                Checkpoint::load(bl, &game.checkpoint, texture_manager)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let pits = level
+            .pits
+            .iter()
+            .map(|p| {
+                let x = p.left as i32 * tile_size.x;
+                let width = p.count as i32 * tile_size.x;
+                glm::ivec4(x, bottom - tile_size.y, width, tile_size.y)
+            })
+            .collect();
+
         Ok(Data {
+            size,
+            spawn,
+            time_limit: Duration::from_secs(level.time_limit),
             background,
             obstacles,
             goal,
@@ -224,6 +305,9 @@ impl<T> Data<T> {
             collectables,
             enemies,
             spikes,
+            checkpoints,
+            pits,
+            platforms,
         })
     }
 
@@ -242,30 +326,197 @@ impl<T> Data<T> {
         let enemies = self.enemies.iter().map(Cat::new).collect();
         Ok(World {
             npc,
+            size: self.size,
+            view: glm::ivec4(0, self.size.y - 720, 1280, 720),
             background: self.background.clone(),
             obstacles: self.obstacles.clone(),
             spikes: self.spikes.clone(),
             goal: self.goal.clone(),
+            checkpoints: self.checkpoints.clone(),
+            pits: self.pits.clone(),
+            platforms: self.platforms.clone(),
             collectables,
             enemies,
         })
     }
 }
 
+fn is_over(pits: &[glm::IVec4], x: f64) -> bool {
+    pits.iter().any(|p| x >= f64::from(p.x) && x < f64::from(p.x + p.z))
+}
+
 impl<T> World<T> {
-    pub fn update(&mut self, duration: Duration) {
+    // takes on the textures and sizes of `data`, loaded again from the same level, while
+    // keeping what happened so far: what was collected, who was beaten, which checkpoints
+    // were reached and where everything moved to
+    pub fn refresh<'t, TL>(
+        &mut self,
+        data: &Data<T>,
+        npc: &data::Player,
+        texture_manager: &mut TextureManager<'t, TL>,
+    ) -> Result<()>
+    where
+        TL: TextureLoader<'t, Texture = T>,
+    {
+        let fresh = data.activate(npc, texture_manager)?;
+        self.background = fresh.background;
+        self.obstacles = fresh.obstacles;
+        self.spikes = fresh.spikes;
+        self.goal = fresh.goal;
+        self.npc = fresh.npc;
+        for (c, f) in self.checkpoints.iter_mut().zip(fresh.checkpoints) {
+            let reached = c.reached;
+            *c = f;
+            c.reached = reached;
+        }
+        for (p, f) in self.platforms.iter_mut().zip(&fresh.platforms) {
+            p.refresh(f);
+        }
+        for c in &mut self.collectables {
+            c.refresh(&data.collectables);
+        }
+        for e in &mut self.enemies {
+            e.refresh(&data.enemies);
+        }
+        Ok(())
+    }
+
+    // called before every update so whatever moves can be drawn in between updates
+    pub fn remember(&mut self) {
+        for e in &mut self.enemies {
+            e.remember();
+        }
+        for p in &mut self.platforms {
+            p.remember();
+        }
+        for c in &mut self.collectables {
+            c.remember();
+        }
+    }
+
+    // 0 draws everything where it was before the last update and 1 where it is now
+    pub fn interpolate(&mut self, alpha: f64) {
+        for e in &mut self.enemies {
+            e.interpolate(alpha);
+        }
+        for p in &mut self.platforms {
+            p.interpolate(alpha);
+        }
+        for c in &mut self.collectables {
+            c.interpolate(alpha);
+        }
+    }
+
+    pub fn watch(&mut self, viewport: &ViewPort) {
+        self.view = viewport.view();
+    }
+
+    // `player` is the center of whoever the enemies go after, if anyone
+    pub fn update(&mut self, duration: Duration, player: Option<glm::DVec2>) {
         for c in &mut self.collectables {
             c.animate(duration);
         }
+        for p in &mut self.platforms {
+            p.update(duration);
+        }
+
+        let floor = f64::from(self.size.y) - FLOOR;
         for e in &mut self.enemies {
-            e.update(duration);
+            let mut step = e.step(duration, player);
+            if step.x != 0. && e.keeps_footing() {
+                // a sliver right below where its front would be after the step
+                let body = e.body();
+                let edge = if step.x < 0. { body.left() } else { body.right() };
+                let front = edge + step.x;
+                let probe = Body {
+                    rectangles: vec![
+                        Rectangle {
+                            top_left: glm::dvec2(front - 1., body.bottom() + 1.),
+                            dims: glm::dvec2(2., 2.),
+                        },
+                    ],
+                    circles: vec![],
+                };
+                let held = self.obstacles.iter().any(|o| o.mtv(&probe).is_some())
+                    || self.platforms.iter().any(|p| p.mtv(&probe).is_some())
+                    || (body.bottom() + 1. >= floor && !is_over(&self.pits, front));
+                if !held {
+                    step.x = 0.;
+                    e.turn();
+                }
+            }
+
+            let mut force = glm::dvec2(0., 0.);
+            let mut body = e.body().nudge(step);
+            for o in &self.obstacles {
+                if let Some(f) = o.mtv(&body) {
+                    force = force + f;
+                    body = body.nudge(f);
+                }
+            }
+            for p in &self.platforms {
+                if let Some(f) = p.mtv(&body) {
+                    // one way platforms only catch cats coming down on them
+                    if p.one_way && (f.y >= 0. || step.y < 0.) {
+                        continue;
+                    }
+                    force = force + f;
+                    body = body.nudge(f);
+                    if f.y < 0. {
+                        let carry = glm::dvec2(p.delta.x, p.delta.y.max(0.));
+                        force = force + carry;
+                        body = body.nudge(carry);
+                    }
+                }
+            }
+            for s in &self.spikes {
+                if let Some(f) = body.mtv(&s.body) {
+                    force = force + f;
+                    body = body.nudge(f);
+                }
+            }
+            let below = body.bottom() - floor;
+            if below > 0. && !is_over(&self.pits, (body.left() + body.right()) / 2.) {
+                force.y -= below;
+            }
+            e.settle(step, force);
+        }
+        // anything that fell out of the level is gone for good
+        let bottom = f64::from(self.size.y);
+        self.enemies.retain(|e| e.dst.y < bottom);
+    }
+
+    // draws in the coins within `range` of `center` by `step` pixels
+    pub fn attract(&mut self, center: glm::DVec2, range: f64, step: f64) {
+        for c in &mut self.collectables {
+            let distance = glm::length(c.body.top_left + c.body.dims / 2. - center);
+            if c.kind == collectable::Kind::Coin && distance <= range {
+                c.pull(center, step);
+            }
+        }
+    }
+
+    pub fn is_over_pit(&self, player: &Player<T>) -> bool {
+        let center = player.dst_rect.x + player.dst_rect.z / 2.;
+        is_over(&self.pits, center)
+    }
+
+    // marks every checkpoint up to `x` as reached; returns the furthest new one
+    pub fn reach_checkpoint(&mut self, x: f64) -> Option<&Checkpoint<T>> {
+        let mut reached = None;
+        for (i, c) in self.checkpoints.iter_mut().enumerate() {
+            if !c.reached && f64::from(c.dst.x) <= x {
+                c.reached = true;
+                reached = Some(i);
+            }
         }
+        reached.map(move |i| &self.checkpoints[i])
     }
 
     pub fn force(&self, player: &Player<T>) -> (glm::DVec2, bool, bool) {
         let mut force = glm::dvec2(0., 0.);
-        let mut legs = player.legs().nudge(player.delta_pos);
-        let mut body = player.body().nudge(player.delta_pos);
+        let mut legs = player.legs().nudge(player.step);
+        let mut body = player.body().nudge(player.step);
         let mut touch_legs = false;
         let mut touch_spikes = false;
 
@@ -278,6 +529,30 @@ impl<T> World<T> {
             }
         }
 
+        // where the feet were before this update; one way platforms only
+        // catch a player that was already above them
+        let feet = player.legs().bottom();
+        for p in &self.platforms {
+            if let Some(f) = p.mtv(&legs) {
+                let landing = f.y < 0. && player.delta_pos.y >= 0.
+                    && feet <= p.top() + p.delta.y.abs() + 1.;
+                if p.one_way && !landing {
+                    continue;
+                }
+                force = force + f;
+                legs = legs.nudge(f);
+                body = body.nudge(f);
+                touch_legs = true;
+                // standing on it carries the player along
+                if f.y < 0. {
+                    let carry = glm::dvec2(p.delta.x, p.delta.y.max(0.));
+                    force = force + carry;
+                    legs = legs.nudge(carry);
+                    body = body.nudge(carry);
+                }
+            }
+        }
+
         for o in &self.obstacles {
             if let Some(f) = o.mtv(&body) {
                 force = force + f;
@@ -286,6 +561,14 @@ impl<T> World<T> {
             }
         }
 
+        for p in self.platforms.iter().filter(|p| !p.one_way) {
+            if let Some(f) = p.mtv(&body) {
+                force = force + f;
+                legs = legs.nudge(f);
+                body = body.nudge(f);
+            }
+        }
+
         for o in &self.obstacles {
             if let Some(f) = o.mtv(&body) {
                 force = force + f;
@@ -324,9 +607,17 @@ impl<T> World<T> {
 
 impl<'t, R: Renderer<'t>> Scene<R> for World<R::Texture> {
     fn show(&self, renderer: &mut R) -> moho::errors::Result<()> {
-        renderer.show(&self.background)?;
+        self.background.show(renderer, &self.view)?;
         renderer.show(&self.goal)?;
+        renderer.set_draw_color(ColorRGBA(0, 0, 0, 255));
+        let pits: Vec<_> = self.pits
+            .iter()
+            .map(|p| Rect::new(p.x, p.y, p.z as u32, p.w as u32))
+            .collect();
+        renderer.fill_rects(&pits)?;
+        self.checkpoints.iter().map(|c| renderer.show(c)).try()?;
         self.obstacles.iter().map(|o| renderer.show(o)).try()?;
+        self.platforms.iter().map(|p| renderer.show(p)).try()?;
         self.collectables.iter().map(|c| renderer.show(c)).try()?;
         self.enemies.iter().map(|c| renderer.show(c)).try()?;
         self.spikes.iter().map(|s| renderer.show(s)).try()?;
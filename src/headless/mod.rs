@@ -0,0 +1,102 @@
+use duck_husky_wedding::{self, Outcome};
+use duck_husky_wedding::replay::Recording;
+use errors::*;
+
+use glm;
+use moho;
+use moho::resource;
+use moho::renderer::{font, Canvas, ColorRGBA, Font, FontLoader, Options, Renderer, Texture,
+                     TextureLoader};
+use sdl2::rect;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NullTexture {
+    dims: glm::UVec2,
+}
+
+impl Texture for NullTexture {
+    fn dims(&self) -> glm::UVec2 {
+        self.dims
+    }
+}
+
+pub struct NullTextureLoader;
+
+impl<'l> resource::Loader<'l, NullTexture> for NullTextureLoader {
+    type Args = str;
+
+    fn load(&'l self, _: &str) -> moho::errors::Result<NullTexture> {
+        Ok(NullTexture::default())
+    }
+}
+
+impl<'l> TextureLoader<'l> for NullTextureLoader {
+    type Texture = NullTexture;
+}
+
+pub struct NullFont {
+    size: u16,
+}
+
+impl Font for NullFont {
+    type Texture = NullTexture;
+
+    fn measure(&self, text: &str) -> moho::errors::Result<glm::UVec2> {
+        let size = u32::from(self.size);
+        Ok(glm::uvec2(text.len() as u32 * size / 2, size))
+    }
+
+    fn texturize(&self, text: &str, _: &ColorRGBA) -> moho::errors::Result<NullTexture> {
+        self.measure(text).map(|dims| NullTexture { dims })
+    }
+}
+
+pub struct NullFontLoader;
+
+impl<'l> resource::Loader<'l, NullFont> for NullFontLoader {
+    type Args = font::Details;
+
+    fn load(&'l self, details: &font::Details) -> moho::errors::Result<NullFont> {
+        Ok(NullFont { size: details.size })
+    }
+}
+
+impl<'l> FontLoader<'l> for NullFontLoader {
+    type Font = NullFont;
+}
+
+pub struct NullCanvas;
+
+impl<'t> Renderer<'t> for NullCanvas {
+    type Texture = NullTexture;
+
+    fn set_draw_color(&mut self, _: ColorRGBA) {}
+
+    fn draw_rects(&mut self, _: &[rect::Rect]) -> moho::errors::Result<()> {
+        Ok(())
+    }
+
+    fn fill_rects(&mut self, _: &[rect::Rect]) -> moho::errors::Result<()> {
+        Ok(())
+    }
+
+    fn copy(&mut self, _: &NullTexture, _: Options) -> moho::errors::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'t> Canvas<'t> for NullCanvas {
+    fn clear(&mut self) {}
+
+    fn present(&mut self) {}
+}
+
+pub fn run(recording: Recording, font_paths: &duck_husky_wedding::font::Paths) -> Result<Outcome> {
+    duck_husky_wedding::simulate(
+        &NullTextureLoader,
+        &NullFontLoader,
+        font_paths,
+        &mut NullCanvas,
+        recording,
+    )
+}
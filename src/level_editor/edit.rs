@@ -0,0 +1,138 @@
+use data::{self, Dimension};
+use super::palette::Tool;
+
+use std::cmp;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Selection {
+    Obstacle(usize),
+    Spike(usize),
+}
+
+fn covers(bl: Dimension, count: Dimension, cell: Dimension) -> bool {
+    cell.x >= bl.x && cell.x < bl.x + count.x && cell.y >= bl.y && cell.y < bl.y + count.y
+}
+
+fn spike_count(s: &data::Spike) -> Dimension {
+    Dimension { x: s.count, y: 1 }
+}
+
+pub fn obstacle_at(level: &data::Level, cell: Dimension) -> Option<usize> {
+    level
+        .obstacles
+        .iter()
+        .rposition(|o| covers(o.bottom_left, o.count, cell))
+}
+
+pub fn spike_at(level: &data::Level, cell: Dimension) -> Option<usize> {
+    level
+        .spikes
+        .iter()
+        .rposition(|s| covers(s.bottom_left, spike_count(s), cell))
+}
+
+// the tiles covered by the selection as (bottom left, count)
+pub fn bounds(level: &data::Level, selection: Selection) -> Option<(Dimension, Dimension)> {
+    match selection {
+        Selection::Obstacle(i) => level.obstacles.get(i).map(|o| (o.bottom_left, o.count)),
+        Selection::Spike(i) => level.spikes.get(i).map(|s| (s.bottom_left, spike_count(s))),
+    }
+}
+
+// returns whether the level changed
+pub fn place(level: &mut data::Level, tool: Tool, cell: Dimension) -> bool {
+    match tool {
+        Tool::Obstacle => level.obstacles.push(data::Obstacle {
+            count: Dimension { x: 1, y: 1 },
+            bottom_left: cell,
+        }),
+        Tool::Spike => level.spikes.push(data::Spike {
+            count: 1,
+            bottom_left: cell,
+            left: None,
+            right: None,
+            bottom: None,
+        }),
+        Tool::Coin if !level.coins.contains(&cell) => level.coins.push(cell),
+        Tool::Gem if !level.gems.contains(&cell) => level.gems.push(cell),
+        Tool::Cat if !level.cats.iter().any(|c| c.bottom_left == cell) => {
+            level.cats.push(data::Enemy::new(data::CatKind::Walking, cell))
+        }
+        Tool::Goal if level.goal != cell => level.goal = cell,
+        Tool::PowerUp(kind) => match level.power_ups.iter_mut().find(|p| p.bottom_left == cell) {
+            Some(ref p) if p.kind == kind => return false,
+            Some(p) => p.kind = kind,
+            None => level.power_ups.push(data::PowerUpSpot {
+                kind,
+                bottom_left: cell,
+            }),
+        },
+        Tool::Erase => return remove(level, cell),
+        _ => return false,
+    }
+    true
+}
+
+// removes the topmost thing in the cell; the goal can only be moved
+pub fn remove(level: &mut data::Level, cell: Dimension) -> bool {
+    if let Some(i) = level.coins.iter().position(|&c| c == cell) {
+        level.coins.remove(i);
+    } else if let Some(i) = level.gems.iter().position(|&g| g == cell) {
+        level.gems.remove(i);
+    } else if let Some(i) = level.power_ups.iter().position(|p| p.bottom_left == cell) {
+        level.power_ups.remove(i);
+    } else if let Some(i) = level.cats.iter().position(|c| c.bottom_left == cell) {
+        level.cats.remove(i);
+    } else if let Some(i) = spike_at(level, cell) {
+        level.spikes.remove(i);
+    } else if let Some(i) = obstacle_at(level, cell) {
+        level.obstacles.remove(i);
+    } else {
+        return false;
+    }
+    true
+}
+
+pub fn delete(level: &mut data::Level, selection: Selection) -> bool {
+    match selection {
+        Selection::Obstacle(i) if i < level.obstacles.len() => {
+            level.obstacles.remove(i);
+        }
+        Selection::Spike(i) if i < level.spikes.len() => {
+            level.spikes.remove(i);
+        }
+        _ => return false,
+    }
+    true
+}
+
+fn grow(count: u32, by: i32) -> u32 {
+    cmp::max(count as i32 + by, 1) as u32
+}
+
+// spikes are always a single row so only their width changes
+pub fn resize(level: &mut data::Level, selection: Selection, by: (i32, i32)) -> bool {
+    match selection {
+        Selection::Obstacle(i) => match level.obstacles.get_mut(i) {
+            Some(o) => {
+                let count = Dimension {
+                    x: grow(o.count.x, by.0),
+                    y: grow(o.count.y, by.1),
+                };
+                let changed = count != o.count;
+                o.count = count;
+                changed
+            }
+            None => false,
+        },
+        Selection::Spike(i) => match level.spikes.get_mut(i) {
+            Some(s) => {
+                let count = grow(s.count, by.0);
+                let changed = count != s.count;
+                s.count = count;
+                changed
+            }
+            None => false,
+        },
+    }
+}
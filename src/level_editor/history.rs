@@ -0,0 +1,50 @@
+use data;
+
+use std::mem;
+
+// every edit keeps a copy of the level as it was before it
+#[derive(Default)]
+pub struct History {
+    undo: Vec<data::Level>,
+    redo: Vec<data::Level>,
+}
+
+impl History {
+    // makes `edit`, keeping a copy of the level from before it only when it changes something
+    pub fn apply<F>(&mut self, level: &mut data::Level, edit: F) -> bool
+    where
+        F: FnOnce(&mut data::Level) -> bool,
+    {
+        let before = level.clone();
+        let changed = edit(level);
+        if changed {
+            self.record(before);
+        }
+        changed
+    }
+
+    pub fn record(&mut self, before: data::Level) {
+        self.undo.push(before);
+        self.redo.clear();
+    }
+
+    pub fn undo(&mut self, level: &mut data::Level) -> bool {
+        match self.undo.pop() {
+            Some(previous) => {
+                self.redo.push(mem::replace(level, previous));
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn redo(&mut self, level: &mut data::Level) -> bool {
+        match self.redo.pop() {
+            Some(next) => {
+                self.undo.push(mem::replace(level, next));
+                true
+            }
+            None => false,
+        }
+    }
+}
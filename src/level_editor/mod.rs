@@ -0,0 +1,365 @@
+mod edit;
+mod history;
+mod palette;
+
+use self::edit::Selection;
+use self::history::History;
+use self::palette::Palette;
+use data;
+use duck_husky_wedding::{self, PlayerKind};
+use duck_husky_wedding::camera::ViewPort;
+use duck_husky_wedding::font::{self, Fonts};
+use duck_husky_wedding::reload::{Changes, Report, Watcher};
+use duck_husky_wedding::world;
+use errors::*;
+
+use glm;
+use moho::input;
+use moho::renderer::{Canvas, ColorRGBA, Font, FontLoader, Renderer, Texture, TextureLoader,
+                     TextureManager};
+use moho::timer::Timer;
+use sdl2::keyboard::Keycode;
+use sdl2::mouse::MouseButton;
+use sdl2::rect::Rect;
+
+use std::rc::Rc;
+
+static TOOL_KEYS: [Keycode; 12] = [
+    Keycode::Num1,
+    Keycode::Num2,
+    Keycode::Num3,
+    Keycode::Num4,
+    Keycode::Num5,
+    Keycode::Num6,
+    Keycode::Num7,
+    Keycode::Num8,
+    Keycode::Num9,
+    Keycode::Num0,
+    Keycode::Minus,
+    Keycode::Equals,
+];
+
+pub struct LevelEditor<'f, 't, TL, FL, R, E>
+where
+    TL: 't + TextureLoader<'t>,
+    FL: 'f + FontLoader<'f>,
+{
+    input_manager: input::Manager<E>,
+    texture_loader: &'t TL,
+    texture_manager: TextureManager<'t, TL>,
+    font_manager: Fonts<'f, FL>,
+    renderer: R,
+    path: String,
+}
+
+impl<'f, 't, TL, FL, R, E> LevelEditor<'f, 't, TL, FL, R, E>
+where
+    TL: TextureLoader<'t>,
+    TL::Texture: Texture,
+    FL: FontLoader<'f>,
+    FL::Font: Font<Texture = TL::Texture>,
+{
+    pub fn new(
+        renderer: R,
+        font_loader: &'f FL,
+        font_paths: &'f font::Paths,
+        texture_loader: &'t TL,
+        input_manager: input::Manager<E>,
+        path: String,
+    ) -> Self {
+        let texture_manager = TextureManager::new(texture_loader);
+        let font_manager = Fonts::new(font_loader, font_paths);
+        LevelEditor {
+            input_manager,
+            texture_loader,
+            texture_manager,
+            font_manager,
+            renderer,
+            path,
+        }
+    }
+
+    pub fn run(&mut self) -> Result<()>
+    where
+        R: Canvas<'t, Texture = TL::Texture>,
+        E: input::EventPump,
+    {
+        let mut game_data = Rc::new(data::Game::load(data::media("game_data.yaml"))?);
+        let mut level = data::Level::load(&self.path)
+            .chain_err(|| format!("cannot load level in path: {:?}", self.path))?;
+        let mut world_data = world::Data::load(&mut self.texture_manager, &level, &game_data)?;
+        let mut world = world_data.activate(&game_data.duck, &mut self.texture_manager)?;
+        let mut palette = Palette::load(&mut self.font_manager)?;
+        let mut history = History::default();
+        let mut selection = None;
+        let mut viewport = ViewPort::new(glm::ivec2(1280, 720));
+        let mut timer = Timer::new();
+        let mut assets = Watcher::new(vec![data::media("game_data.yaml"), data::media("sprites")]);
+        let mut file = Watcher::new(vec![self.path.clone()]);
+        let mut report = Report::default();
+        // the level file changed on disk and has not been loaded yet
+        let mut outside = false;
+        loop {
+            let game_time = timer.update();
+            let input = self.input_manager.update();
+            if input.game_quit() {
+                break;
+            };
+            world.update(game_time.since_update, None);
+
+            let touched = assets.poll(game_time.since_update);
+            if !touched.is_empty() {
+                // the texture manager cannot forget single textures
+                if Changes::sort(touched).sprites {
+                    self.texture_manager = TextureManager::new(self.texture_loader);
+                }
+                let texture_manager = &mut self.texture_manager;
+                let result = data::Game::load(data::media("game_data.yaml"))
+                    .chain_err(|| "cannot load the game data")
+                    .and_then(|g| {
+                        let d = world::Data::load(texture_manager, &level, &g)?;
+                        let w = d.activate(&g.duck, texture_manager)?;
+                        Ok((g, d, w))
+                    });
+                report = match result {
+                    Ok((g, d, w)) => {
+                        game_data = Rc::new(g);
+                        world_data = d;
+                        world = w;
+                        world.watch(&viewport);
+                        Report::default()
+                    }
+                    Err(err) => Report::load(&mut self.font_manager, &err)?,
+                };
+            }
+            // edits that are not saved yet win over the file until they are saved or dropped
+            if !file.poll(game_time.since_update).is_empty() {
+                outside = true;
+                if palette.dirty {
+                    let notice = Error::from(
+                        "the level file changed on disk: R loads it over your edits, \
+                         Ctrl+S keeps yours",
+                    );
+                    report = Report::load(&mut self.font_manager, &notice)?;
+                }
+            }
+
+            let ctrl = input.is_key_down(Keycode::LCtrl) || input.is_key_down(Keycode::RCtrl);
+            let shift = input.is_key_down(Keycode::LShift) || input.is_key_down(Keycode::RShift);
+            let tile: glm::IVec2 = game_data.ground.out_size.into();
+            let bottom = level.size(game_data.ground.out_size).y;
+            let mouse = input.mouse_coords();
+            let hovered = if palette.contains(mouse) {
+                None
+            } else {
+                cell_at(mouse + viewport.translation(), tile, bottom)
+            };
+            let mut changed = false;
+            let mut reloaded = false;
+
+            if let Some(t) = TOOL_KEYS.iter().position(|&k| input.did_press_key(k)) {
+                palette.tool = palette::TOOLS[t];
+            }
+            if input.did_click_mouse(MouseButton::Left) {
+                if let Some(t) = palette.tool_at(mouse) {
+                    palette.tool = t;
+                }
+            }
+
+            if let Some(cell) = hovered {
+                if input.did_click_mouse(MouseButton::Left) {
+                    // clicking on something of the same kind picks it instead of stacking
+                    let picked = match palette.tool {
+                        palette::Tool::Obstacle => {
+                            edit::obstacle_at(&level, cell).map(Selection::Obstacle)
+                        }
+                        palette::Tool::Spike => edit::spike_at(&level, cell).map(Selection::Spike),
+                        _ => None,
+                    };
+                    if picked.is_some() {
+                        selection = picked;
+                    } else {
+                        let tool = palette.tool;
+                        changed = history.apply(&mut level, |l| edit::place(l, tool, cell));
+                        selection = match palette.tool {
+                            palette::Tool::Obstacle => {
+                                Some(Selection::Obstacle(level.obstacles.len() - 1))
+                            }
+                            palette::Tool::Spike => Some(Selection::Spike(level.spikes.len() - 1)),
+                            _ => None,
+                        };
+                    }
+                } else if input.did_click_mouse(MouseButton::Right) {
+                    changed = history.apply(&mut level, |l| edit::remove(l, cell));
+                    selection = None;
+                }
+            }
+
+            let mut t = glm::ivec2(0, 0);
+            if let Some(s) = selection {
+                if shift {
+                    let mut by = (0, 0);
+                    if input.did_press_key(Keycode::Left) {
+                        by.0 -= 1;
+                    }
+                    if input.did_press_key(Keycode::Right) {
+                        by.0 += 1;
+                    }
+                    if input.did_press_key(Keycode::Up) {
+                        by.1 += 1;
+                    }
+                    if input.did_press_key(Keycode::Down) {
+                        by.1 -= 1;
+                    }
+                    changed |= history.apply(&mut level, |l| edit::resize(l, s, by));
+                }
+                if input.did_press_key(Keycode::Delete) {
+                    changed |= history.apply(&mut level, |l| edit::delete(l, s));
+                    selection = None;
+                }
+            }
+            if !shift {
+                if input.is_key_down(Keycode::Left) {
+                    t.x -= 5;
+                }
+                if input.is_key_down(Keycode::Right) {
+                    t.x += 5;
+                }
+                if input.is_key_down(Keycode::Up) {
+                    t.y -= 5;
+                }
+                if input.is_key_down(Keycode::Down) {
+                    t.y += 5;
+                }
+            }
+            viewport.translate(t);
+            world.watch(&viewport);
+
+            // edits made above are already in the history
+            let edited = changed;
+            if !edited && ctrl && input.did_press_key(Keycode::Z) {
+                changed = history.undo(&mut level);
+                selection = None;
+            } else if !edited && ctrl && input.did_press_key(Keycode::Y) {
+                changed = history.redo(&mut level);
+                selection = None;
+            } else if !edited && ((outside && !palette.dirty) || input.did_press_key(Keycode::R)) {
+                let path = &self.path;
+                let loaded = data::Level::load(path)
+                    .chain_err(|| format!("cannot load level in path: {:?}", path));
+                report = match loaded {
+                    Ok(l) => {
+                        history.record(level);
+                        level = l;
+                        changed = true;
+                        reloaded = true;
+                        selection = None;
+                        outside = false;
+                        Report::default()
+                    }
+                    Err(err) => Report::load(&mut self.font_manager, &err)?,
+                };
+            }
+
+            if changed {
+                palette.dirty = !reloaded;
+                let texture_manager = &mut self.texture_manager;
+                let result = world::Data::load(texture_manager, &level, &game_data).and_then(|d| {
+                    let w = d.activate(&game_data.duck, texture_manager)?;
+                    Ok((d, w))
+                });
+                match result {
+                    Ok((d, w)) => {
+                        world_data = d;
+                        world = w;
+                        world.watch(&viewport);
+                        if !outside {
+                            report = Report::default();
+                        }
+                    }
+                    Err(err) => report = Report::load(&mut self.font_manager, &err)?,
+                }
+            }
+            if input.did_press_key(Keycode::Tab) {
+                palette.player = match palette.player {
+                    PlayerKind::Duck => PlayerKind::Husky,
+                    PlayerKind::Husky => PlayerKind::Duck,
+                };
+            }
+            let playtest = input.did_press_key(Keycode::P);
+            if ctrl && input.did_press_key(Keycode::S) {
+                let path = &self.path;
+                let saved = level
+                    .save(path)
+                    .chain_err(|| format!("cannot save level in path: {:?}", path));
+                match saved {
+                    Ok(()) => {
+                        palette.dirty = false;
+                        file.forget();
+                        if outside {
+                            outside = false;
+                            report = Report::default();
+                        }
+                    }
+                    Err(err) => report = Report::load(&mut self.font_manager, &err)?,
+                }
+            }
+
+            //draw
+            self.renderer.clear();
+            {
+                let mut camera = viewport.camera(&mut self.renderer);
+                camera.show(&world)?;
+                if let Some(cell) = hovered {
+                    camera.set_draw_color(ColorRGBA(255, 255, 255, 255));
+                    let one = data::Dimension { x: 1, y: 1 };
+                    camera.draw_rects(&[tiles(cell, one, tile, bottom)])?;
+                }
+                if let Some((bl, count)) = selection.and_then(|s| edit::bounds(&level, s)) {
+                    camera.set_draw_color(ColorRGBA(255, 255, 0, 255));
+                    camera.draw_rects(&[tiles(bl, count, tile, bottom)])?;
+                }
+            }
+            self.renderer.show(&palette)?;
+            self.renderer.show(&report)?;
+            self.renderer.present();
+
+            if playtest {
+                let center = viewport.translation() + glm::ivec2(640, 360);
+                let played = duck_husky_wedding::playtest(
+                    &mut self.texture_manager,
+                    &mut self.font_manager,
+                    &mut self.renderer,
+                    &mut self.input_manager,
+                    Rc::clone(&game_data),
+                    &world_data,
+                    palette.player,
+                    center,
+                );
+                if let Err(err) = played {
+                    report = Report::load(&mut self.font_manager, &err)?;
+                }
+                // don't fast forward the editor by however long the playtest took
+                timer = Timer::new();
+            }
+        }
+        Ok(())
+    }
+}
+
+// the tile under a point in a level ending at `bottom`, counted from the bottom left
+fn cell_at(point: glm::IVec2, tile: glm::IVec2, bottom: i32) -> Option<data::Dimension> {
+    if point.x < 0 || point.y >= bottom {
+        return None;
+    }
+    Some(data::Dimension {
+        x: (point.x / tile.x) as u32,
+        y: ((bottom - 1 - point.y) / tile.y) as u32,
+    })
+}
+
+fn tiles(bl: data::Dimension, count: data::Dimension, tile: glm::IVec2, bottom: i32) -> Rect {
+    let x = bl.x as i32 * tile.x;
+    let y = bottom - (bl.y + count.y) as i32 * tile.y;
+    Rect::new(x, y, count.x * tile.x as u32, count.y * tile.y as u32)
+}
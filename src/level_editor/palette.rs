@@ -0,0 +1,187 @@
+use data::PowerUpKind;
+use duck_husky_wedding::PlayerKind;
+use duck_husky_wedding::font;
+use errors::*;
+
+use glm;
+use moho;
+use moho::renderer::{align, options, ColorRGBA, Font, Renderer, Scene, Texture};
+use sdl2::rect::Rect;
+
+pub const LEFT: i32 = 1080;
+const TOOLS_TOP: i32 = 60;
+const ROW_HEIGHT: i32 = 30;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Tool {
+    Obstacle,
+    Spike,
+    Coin,
+    Gem,
+    Cat,
+    Goal,
+    Erase,
+    PowerUp(PowerUpKind),
+}
+
+impl Tool {
+    fn label(&self) -> &'static str {
+        match *self {
+            Tool::Obstacle => "1 Obstacle",
+            Tool::Spike => "2 Spike",
+            Tool::Coin => "3 Coin",
+            Tool::Gem => "4 Gem",
+            Tool::Cat => "5 Cat",
+            Tool::Goal => "6 Goal",
+            Tool::Erase => "7 Erase",
+            Tool::PowerUp(PowerUpKind::DoubleJump) => "8 Double Jump",
+            Tool::PowerUp(PowerUpKind::SpeedBoost) => "9 Speed Boost",
+            Tool::PowerUp(PowerUpKind::Invincibility) => "0 Invincibility",
+            Tool::PowerUp(PowerUpKind::ExtraTime) => "- Extra Time",
+            Tool::PowerUp(PowerUpKind::Magnet) => "= Magnet",
+        }
+    }
+}
+
+pub static TOOLS: [Tool; 12] = [
+    Tool::Obstacle,
+    Tool::Spike,
+    Tool::Coin,
+    Tool::Gem,
+    Tool::Cat,
+    Tool::Goal,
+    Tool::Erase,
+    Tool::PowerUp(PowerUpKind::DoubleJump),
+    Tool::PowerUp(PowerUpKind::SpeedBoost),
+    Tool::PowerUp(PowerUpKind::Invincibility),
+    Tool::PowerUp(PowerUpKind::ExtraTime),
+    Tool::PowerUp(PowerUpKind::Magnet),
+];
+
+static HELP: [&'static str; 8] = [
+    "Left click: place",
+    "Right click: remove",
+    "Shift+Arrows: resize",
+    "Delete: remove selected",
+    "Ctrl+Z / Ctrl+Y: undo/redo",
+    "Ctrl+S: save",
+    "R: reload from file",
+    "Tab: switch player",
+];
+
+struct Entry<T> {
+    idle: T,
+    selected: T,
+}
+
+pub struct Palette<T> {
+    title: T,
+    saved: T,
+    unsaved: T,
+    duck: T,
+    husky: T,
+    entries: Vec<Entry<T>>,
+    help: Vec<T>,
+    pub tool: Tool,
+    pub player: PlayerKind,
+    pub dirty: bool,
+}
+
+impl<T> Palette<T> {
+    pub fn load<FM>(font_manager: &mut FM) -> Result<Self>
+    where
+        FM: font::Manager,
+        FM::Font: Font<Texture = T>,
+    {
+        let white = ColorRGBA(255, 255, 255, 255);
+        let yellow = ColorRGBA(255, 255, 0, 255);
+
+        let title = {
+            let font = font_manager.load(font::Kind::KenPixel, 40)?;
+            font.texturize("Palette", &yellow)
+        }?;
+        let font = font_manager.load(font::Kind::KenPixel, 24)?;
+        let saved = font.texturize("Saved", &white)?;
+        let unsaved = font.texturize("Unsaved changes", &yellow)?;
+        let entries = TOOLS
+            .iter()
+            .map(|t| -> Result<Entry<T>> {
+                Ok(Entry {
+                    idle: font.texturize(t.label(), &white)?,
+                    selected: font.texturize(t.label(), &yellow)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let font = font_manager.load(font::Kind::KenPixel, 16)?;
+        let duck = font.texturize("P: playtest as Duck", &white)?;
+        let husky = font.texturize("P: playtest as Husky", &white)?;
+        let help = HELP.iter()
+            .map(|h| font.texturize(h, &white).map_err(Into::into))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Palette {
+            title,
+            saved,
+            unsaved,
+            duck,
+            husky,
+            entries,
+            help,
+            tool: Tool::Obstacle,
+            player: PlayerKind::Duck,
+            dirty: false,
+        })
+    }
+
+    pub fn contains(&self, mouse: glm::IVec2) -> bool {
+        mouse.x >= LEFT
+    }
+
+    pub fn tool_at(&self, mouse: glm::IVec2) -> Option<Tool> {
+        if !self.contains(mouse) || mouse.y < TOOLS_TOP {
+            return None;
+        }
+        TOOLS
+            .get(((mouse.y - TOOLS_TOP) / ROW_HEIGHT) as usize)
+            .cloned()
+    }
+}
+
+impl<'t, R: Renderer<'t>> Scene<R> for Palette<R::Texture>
+where
+    R::Texture: Texture,
+{
+    fn show(&self, renderer: &mut R) -> moho::errors::Result<()> {
+        renderer.set_draw_color(ColorRGBA(30, 30, 30, 255));
+        renderer.fill_rects(&[Rect::new(LEFT, 0, (1280 - LEFT) as u32, 720)])?;
+
+        let left = LEFT + 10;
+        renderer.copy(&self.title, options::at(align::left(left).top(5)))?;
+
+        for (i, (e, t)) in self.entries.iter().zip(TOOLS.iter()).enumerate() {
+            let texture = if *t == self.tool { &e.selected } else { &e.idle };
+            let middle = align::middle(TOOLS_TOP + ROW_HEIGHT * i as i32 + ROW_HEIGHT / 2);
+            renderer.copy(texture, options::at(middle.left(left)))?;
+        }
+
+        let mut top = align::top(TOOLS_TOP + ROW_HEIGHT * TOOLS.len() as i32 + 20);
+        for h in &self.help {
+            let options = options::at(top.left(left));
+            top = top.nudge(4 + h.dims().y as i32);
+            renderer.copy(h, options)?;
+        }
+
+        let player = match self.player {
+            PlayerKind::Duck => &self.duck,
+            PlayerKind::Husky => &self.husky,
+        };
+        renderer.copy(player, options::at(align::left(left).bottom(680)))?;
+
+        let status = if self.dirty {
+            &self.unsaved
+        } else {
+            &self.saved
+        };
+        renderer.copy(status, options::at(align::left(left).bottom(710)))
+    }
+}
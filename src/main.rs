@@ -7,13 +7,22 @@ extern crate sdl2;
 extern crate serde_derive;
 extern crate serde_yaml;
 
+mod cli;
 mod duck_husky_wedding;
-mod level_viewer;
+mod headless;
+mod level_editor;
+mod validate;
 mod data;
 mod utils;
 
-use duck_husky_wedding::DuckHuskyWedding;
-use level_viewer::LevelViewer;
+use cli::Command;
+use duck_husky_wedding::{DuckHuskyWedding, Launch};
+use duck_husky_wedding::audio::Mixer;
+use duck_husky_wedding::font;
+use duck_husky_wedding::gamepad::Gamepads;
+use duck_husky_wedding::replay::{Recording, Script};
+use duck_husky_wedding::settings::{self, DisplayMode};
+use level_editor::LevelEditor;
 
 use moho::input;
 use sdl2::image::{INIT_JPG, INIT_PNG};
@@ -30,53 +39,161 @@ pub mod errors {
     }
 }
 
+// `error` after `prefix` and then each of its causes on a line of its own
+fn print_error(prefix: &str, error: &errors::Error) {
+    println!("{}{}", prefix, error);
+    for e in error.iter().skip(1) {
+        println!("    caused by: {}", e);
+    }
+}
+
 fn main() {
+    let cli = match cli::parse(std::env::args().skip(1)) {
+        Ok(c) => c,
+        Err(e) => {
+            println!("{}\n\n{}", e, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+    if let Err(e) = data::locate(cli.data_dir) {
+        print_error("", &e);
+        println!("\n{}", cli::USAGE);
+        std::process::exit(2);
+    }
+    let font_paths = font::Paths::new();
+
+    let mut windowed = false;
+    let mut level_editor = None;
+    let mut launch = Launch::Menu;
+    match cli.command {
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            return;
+        }
+        Command::Validate(path) => {
+            let issues = match validate::run(&path) {
+                Ok(i) => i,
+                Err(e) => {
+                    print_error(&format!("{}: ", path), &e);
+                    std::process::exit(1);
+                }
+            };
+            for i in &issues {
+                println!("{}: {}", path, i);
+            }
+            let failed = issues.iter().any(validate::Issue::is_error);
+            if !failed {
+                println!("{}: ok", path);
+            }
+            std::process::exit(if failed { 1 } else { 0 });
+        }
+        Command::Replay {
+            file,
+            headless,
+            script,
+        } => if headless || script {
+            let recording = if script {
+                Script::load(file).map(Script::into_recording)
+            } else {
+                Recording::load(file)
+            };
+            match recording.and_then(|r| headless::run(r, &font_paths)) {
+                Ok(outcome) => std::process::exit(if outcome.finished { 0 } else { 1 }),
+                Err(e) => {
+                    print_error("replay failed: ", &e);
+                    std::process::exit(3);
+                }
+            }
+        } else {
+            launch = Launch::Replay(file);
+        },
+        Command::View(level) => {
+            level_editor = Some(level.unwrap_or_else(|| data::media("level.yaml")));
+        }
+        Command::Play(p) => {
+            windowed = p.windowed;
+            if p.level.is_some() || p.character.is_some() {
+                launch = Launch::Play {
+                    level: p.level,
+                    player: p.character,
+                };
+            }
+        }
+    }
+
     const WINDOW_WIDTH: u32 = 1280;
     const WINDOW_HEIGHT: u32 = 720;
     let name = "Husky Loves Ducky";
 
+    let mut settings = settings::get();
+    // only for this run; the saved settings are left alone
+    if windowed {
+        settings.display = DisplayMode::Windowed;
+    }
     let sdl_ctx = sdl2::init().unwrap();
     let video_ctx = sdl_ctx.video().unwrap();
-    let bounds = video_ctx.display_bounds(0).unwrap();
     let _image_ctx = sdl2::image::init(INIT_PNG | INIT_JPG).unwrap();
 
-    let window = video_ctx
-        .window(name, bounds.width(), bounds.height())
-        .position_centered()
-        .opengl()
-        .fullscreen()
-        .build()
-        .unwrap();
+    let mut window = video_ctx.window(name, settings.window.x, settings.window.y);
+    window.position_centered().opengl();
+    match settings.display {
+        DisplayMode::Windowed => {}
+        DisplayMode::Fullscreen => {
+            window.fullscreen();
+        }
+        DisplayMode::Borderless => {
+            window.fullscreen_desktop();
+        }
+    }
+    let window = window.build().unwrap();
 
-    let mut renderer = window
-        .into_canvas()
-        .accelerated()
-        .present_vsync()
-        .build()
-        .unwrap();
+    let mut canvas = window.into_canvas().accelerated();
+    if settings.vsync {
+        canvas = canvas.present_vsync();
+    }
+    let mut renderer = canvas.build().unwrap();
     let creator = renderer.texture_creator();
 
     renderer
         .set_logical_size(WINDOW_WIDTH, WINDOW_HEIGHT)
         .unwrap();
+    // lets overlays such as the respawn fade be drawn translucent
+    renderer.set_blend_mode(sdl2::render::BlendMode::Blend);
     renderer.clear();
     renderer.present();
     let input_manager = input::Manager::new(sdl_ctx.event_pump().unwrap());
 
-    let mut level_viewer = false;
-    for argument in std::env::args() {
-        if argument == "--l" {
-            level_viewer = true;
-            break;
-        }
-    }
-
-    if level_viewer {
-        let mut game = LevelViewer::new(renderer, &creator, input_manager);
-        game.run().unwrap();
+    let font_loader = moho::renderer::sdl2::font::Loader::load(&creator).unwrap();
+    if let Some(level_path) = level_editor {
+        let mut editor = LevelEditor::new(
+            renderer,
+            &font_loader,
+            &font_paths,
+            &creator,
+            input_manager,
+            level_path,
+        );
+        editor.run().unwrap();
     } else {
-        let font_loader = moho::renderer::sdl2::font::Loader::load(&creator).unwrap();
-        let mut game = DuckHuskyWedding::new(renderer, &font_loader, &creator, input_manager);
-        game.run().unwrap();
+        // the game is still playable with a keyboard if controllers are unavailable
+        let gamepads = sdl_ctx.game_controller().ok().map(Gamepads::new);
+        // and silent if there is nothing to play sounds on
+        let mixer = match sdl_ctx.audio().and_then(Mixer::open) {
+            Ok(m) => Some(m),
+            Err(e) => {
+                println!("playing without sound: {}", e);
+                None
+            }
+        };
+        let mut game = DuckHuskyWedding::new(
+            renderer,
+            &font_loader,
+            &font_paths,
+            &creator,
+            input_manager,
+            gamepads,
+            mixer,
+        );
+        game.run(launch).unwrap();
     }
 }
@@ -0,0 +1,167 @@
+mod reach;
+
+use self::reach::{Cell, Grid};
+use data::{self, Dimension};
+use errors::*;
+
+use std::cmp;
+use std::fmt;
+use std::path::Path;
+
+pub enum Issue {
+    // the level still plays but maybe not as intended, e.g. overlapping walls
+    Warning(String),
+    Error(String),
+}
+
+impl Issue {
+    pub fn is_error(&self) -> bool {
+        match *self {
+            Issue::Warning(_) => false,
+            Issue::Error(_) => true,
+        }
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Issue::Warning(ref m) => write!(f, "warning: {}", m),
+            Issue::Error(ref m) => write!(f, "error: {}", m),
+        }
+    }
+}
+
+fn overlaps(a: (Dimension, Dimension), b: (Dimension, Dimension)) -> bool {
+    let ((a_bl, a_count), (b_bl, b_count)) = (a, b);
+    a_bl.x < b_bl.x + b_count.x && b_bl.x < a_bl.x + a_count.x && a_bl.y < b_bl.y + b_count.y
+        && b_bl.y < a_bl.y + a_count.y
+}
+
+fn cell(d: Dimension) -> Cell {
+    (d.x as i32, d.y as i32)
+}
+
+// loads a level against the game data and lists everything wrong with it
+pub fn run(path: &str) -> Result<Vec<Issue>> {
+    let game = data::Game::load(data::media("game_data.yaml"))?;
+    let level = data::Level::load(path)
+        .chain_err(|| format!("cannot load level in path: {:?}", path))?;
+    let grid = Grid::new(&level, &game);
+
+    let mut issues = vec![];
+    check_obstacles(&level, &mut issues);
+    check_textures(&level, &game, &mut issues);
+    check_collectables(&level, &grid, &mut issues);
+    check_cats(&level, &grid, &mut issues);
+    let spawn = {
+        let height = cmp::max(game.duck.out_size.y, game.husky.out_size.y);
+        level.spawn_tile(game.ground.out_size, height)
+    };
+    check_reachability(&level, &grid, spawn, &mut issues);
+    Ok(issues)
+}
+
+fn check_obstacles(level: &data::Level, issues: &mut Vec<Issue>) {
+    let bounds: Vec<_> = level
+        .obstacles
+        .iter()
+        .map(|o| (o.bottom_left, o.count))
+        .collect();
+    for (i, &a) in bounds.iter().enumerate() {
+        for (j, &b) in bounds.iter().enumerate().skip(i + 1) {
+            if overlaps(a, b) {
+                issues.push(Issue::Warning(format!(
+                    "obstacle {} at {:?} overlaps obstacle {} at {:?}",
+                    i, a.0, j, b.0
+                )));
+            }
+        }
+    }
+}
+
+// spikes only load their ground edges once the level is built
+fn check_textures(level: &data::Level, game: &data::Game, issues: &mut Vec<Issue>) {
+    for (i, s) in level.spikes.iter().enumerate() {
+        let kinds = s.left.iter().chain(s.right.iter()).chain(s.bottom.iter());
+        for k in kinds {
+            let texture = match *k {
+                data::GroundKind::Top => &game.ground.top,
+                data::GroundKind::Middle => &game.ground.center,
+            };
+            if !Path::new(&texture.path()).exists() {
+                issues.push(Issue::Error(format!(
+                    "spike {} needs the missing texture {:?}",
+                    i,
+                    texture.path()
+                )));
+            }
+        }
+    }
+}
+
+fn check_collectables(level: &data::Level, grid: &Grid, issues: &mut Vec<Issue>) {
+    let collectables = level
+        .coins
+        .iter()
+        .map(|c| ("coin", c))
+        .chain(level.gems.iter().map(|g| ("gem", g)))
+        .chain(level.power_ups.iter().map(|p| ("power up", &p.bottom_left)));
+    for (kind, c) in collectables {
+        if grid.is_ground(cell(*c)) {
+            issues.push(Issue::Error(format!("{} at {:?} is inside the ground", kind, c)));
+        }
+    }
+}
+
+// cats find their own way around but cannot start out stuck in the ground
+fn check_cats(level: &data::Level, grid: &Grid, issues: &mut Vec<Issue>) {
+    for c in &level.cats {
+        if grid.is_ground(cell(c.bottom_left)) {
+            let message = format!("cat at {:?} is inside the ground", c.bottom_left);
+            issues.push(Issue::Error(message));
+        }
+    }
+}
+
+fn check_reachability(
+    level: &data::Level,
+    grid: &Grid,
+    spawn: Dimension,
+    issues: &mut Vec<Issue>,
+) {
+    let reachable = grid.explore(cell(spawn));
+    let height = grid.player().y as i32;
+    let touches = |(x, y): Cell| {
+        reachable.iter().any(|&(sx, sy)| {
+            let rows = if y >= sy + height {
+                y - (sy + height - 1)
+            } else if y < sy {
+                y - sy
+            } else {
+                0
+            };
+            grid.reach(rows).map_or(false, |r| (x - sx).abs() <= r)
+        })
+    };
+
+    let collectables = level
+        .coins
+        .iter()
+        .map(|c| ("coin", c))
+        .chain(level.gems.iter().map(|g| ("gem", g)))
+        .chain(level.power_ups.iter().map(|p| ("power up", &p.bottom_left)));
+    for (kind, c) in collectables {
+        if !touches(cell(*c)) {
+            issues.push(Issue::Error(format!("{} at {:?} cannot be reached", kind, c)));
+        }
+    }
+
+    // the run is over as soon as the player's front gets to the goal
+    let width = grid.player().x as i32;
+    let goal = level.goal.x as i32;
+    if !reachable.iter().any(|&(x, _)| x + width >= goal) {
+        let message = format!("the goal at {:?} cannot be reached", level.goal);
+        issues.push(Issue::Error(message));
+    }
+}
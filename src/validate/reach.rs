@@ -0,0 +1,206 @@
+use data::{self, Dimension};
+use duck_husky_wedding;
+
+use std::cmp;
+use std::collections::{HashSet, VecDeque};
+
+pub type Cell = (i32, i32);
+
+// no jump or fall is ever checked for more rows than this
+const TOP: i32 = 30;
+
+fn tiles(px: f64, tile: u32) -> i32 {
+    (px / f64::from(tile)).ceil() as i32
+}
+
+// the tile grid of a level as the player sees it, counted from the bottom left
+pub struct Grid {
+    ground: HashSet<Cell>,
+    // can be stood on but never block, e.g. every stop along a platform's path
+    ledges: HashSet<Cell>,
+    spikes: HashSet<Cell>,
+    pits: HashSet<i32>,
+    // how far the player can be from the take off column while at least n rows higher,
+    // indexed by n + `drop`
+    reach: Vec<Option<i32>>,
+    drop: i32,
+    player: Dimension,
+    width: i32,
+    height: i32,
+}
+
+impl Grid {
+    pub fn new(level: &data::Level, game: &data::Game) -> Self {
+        let tile = game.ground.out_size;
+        let player = {
+            let duck = game.duck.out_size;
+            let husky = game.husky.out_size;
+            let x = cmp::max(duck.x, husky.x);
+            let y = cmp::max(duck.y, husky.y);
+            Dimension {
+                x: tiles(f64::from(x), tile.x) as u32,
+                y: tiles(f64::from(y), tile.y) as u32,
+            }
+        };
+
+        let mut ground = HashSet::new();
+        for o in &level.obstacles {
+            let bl = o.bottom_left;
+            for x in bl.x..bl.x + o.count.x {
+                for y in bl.y..bl.y + o.count.y {
+                    ground.insert((x as i32, y as i32));
+                }
+            }
+        }
+        let mut ledges = HashSet::new();
+        for p in &level.platforms {
+            for stop in &p.path {
+                for x in stop.x..stop.x + p.count {
+                    ledges.insert((x as i32, stop.y as i32));
+                }
+            }
+        }
+        let spikes = level
+            .spikes
+            .iter()
+            .flat_map(|s| {
+                let bl = s.bottom_left;
+                (bl.x..bl.x + s.count).map(move |x| (x as i32, bl.y as i32))
+            })
+            .collect();
+        let pits = level
+            .pits
+            .iter()
+            .flat_map(|p| (p.left..p.left + p.count).map(|x| x as i32))
+            .collect();
+
+        // nothing falls further than the whole level
+        let arc = duck_husky_wedding::jump_arc(f64::from(level.size(tile).y));
+        let drop = TOP;
+        let reach = (-drop..TOP)
+            .map(|n| {
+                let height = f64::from(n) * f64::from(tile.y);
+                arc.iter()
+                    .filter(|p| -p.y >= height)
+                    .map(|p| (p.x / f64::from(tile.x)) as i32)
+                    .max()
+            })
+            .collect();
+
+        let width = ground
+            .iter()
+            .chain(ledges.iter())
+            .map(|&(x, _)| x)
+            .chain(Some(level.goal.x as i32))
+            .max()
+            .unwrap_or(0) + 1;
+        let height = level.size(tile).y / tile.y as i32;
+
+        Grid {
+            ground,
+            ledges,
+            spikes,
+            pits,
+            reach,
+            drop,
+            player,
+            width,
+            height,
+        }
+    }
+
+    pub fn is_ground(&self, cell: Cell) -> bool {
+        self.ground.contains(&cell)
+    }
+
+    // how many columns away something n rows above the feet can still be touched
+    pub fn reach(&self, rows: i32) -> Option<i32> {
+        let i = rows + self.drop;
+        if i < 0 {
+            return self.reach.first().cloned().and_then(|r| r);
+        }
+        self.reach.get(i as usize).cloned().and_then(|r| r)
+    }
+
+    pub fn player(&self) -> Dimension {
+        self.player
+    }
+
+    fn is_free(&self, (x, y): Cell) -> bool {
+        x >= 0 && x <= self.width
+            && (y..y + self.player.y as i32).all(|y| !self.ground.contains(&(x, y)))
+    }
+
+    // below the first row the player is held up everywhere but over a pit
+    fn holds_up(&self, (x, y): Cell) -> bool {
+        let below = (x, y - 1);
+        if self.spikes.contains(&below) {
+            false
+        } else {
+            self.ground.contains(&below) || self.ledges.contains(&below)
+                || (y == 1 && !self.pits.contains(&x))
+        }
+    }
+
+    // the player only needs part of its width over something to stand on it
+    fn is_supported(&self, (x, y): Cell) -> bool {
+        (x..x + self.player.x as i32).any(|x| self.holds_up((x, y)))
+    }
+
+    pub fn can_stand(&self, cell: Cell) -> bool {
+        cell.1 >= 1 && self.is_free(cell) && self.is_supported(cell)
+            && !self.spikes.contains(&cell)
+    }
+
+    // where something dropped at `cell` comes to rest, if anywhere
+    pub fn fall(&self, (x, y): Cell) -> Option<Cell> {
+        (1..y + 1)
+            .rev()
+            .take_while(|&y| self.is_free((x, y)))
+            .find(|&y| self.can_stand((x, y)))
+            .map(|y| (x, y))
+    }
+
+    // every place the player can stand on starting from `start`; optimistic in that it
+    // ignores anything in the way during a jump, so it only flags what cannot be reached
+    pub fn explore(&self, start: Cell) -> HashSet<Cell> {
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::new();
+        if let Some(s) = self.fall(start) {
+            seen.insert(s);
+            queue.push_back(s);
+        }
+
+        while let Some((x, y)) = queue.pop_front() {
+            let mut next = vec![];
+            for &dir in &[-1, 1] {
+                let side = (x + dir, y);
+                if self.can_stand(side) {
+                    next.push(side);
+                } else if let Some(c) = self.fall(side) {
+                    next.push(c);
+                }
+            }
+            for rows in cmp::max(1 - y, -TOP)..cmp::min(self.height - y, TOP) {
+                let columns = match self.reach(rows) {
+                    Some(c) => c,
+                    None => continue,
+                };
+                for dx in 1..columns + 1 {
+                    for &dir in &[-1, 1] {
+                        let landing = (x + dir * dx, y + rows);
+                        if self.can_stand(landing) {
+                            next.push(landing);
+                        }
+                    }
+                }
+            }
+            for c in next {
+                if seen.insert(c) {
+                    queue.push_back(c);
+                }
+            }
+        }
+        seen
+    }
+}